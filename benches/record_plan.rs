@@ -0,0 +1,48 @@
+use std::hint::black_box;
+use criterion::{Criterion, criterion_group, criterion_main};
+use csharp_binary_encoding::{BinaryReader, BinaryWriter, FieldType, FieldValue, RecordPlan};
+
+const NUM_RECORDS: usize = 1000;
+
+fn record_data(record: &[FieldValue]) -> Vec<u8> {
+    let mut data: Vec<u8> = Vec::new();
+    let mut writer = BinaryWriter::new(&mut data);
+    for _ in 0..NUM_RECORDS {
+        writer.write_record(record).unwrap();
+    }
+    data
+}
+
+fn bench_record(c: &mut Criterion, name: &str, record: &[FieldValue]) {
+    let schema: Vec<FieldType> = record.iter().map(FieldValue::field_type).collect();
+    let data = record_data(record);
+    let plan = RecordPlan::new(&schema);
+    let mut group = c.benchmark_group(name);
+    group.bench_function("read_record", |b| b.iter(|| {
+        let mut reader = BinaryReader::new(data.as_slice());
+        for _ in 0..NUM_RECORDS {
+            black_box(reader.read_record(black_box(&schema)).unwrap());
+        }
+    }));
+    group.bench_function("read_planned_record", |b| b.iter(|| {
+        let mut reader = BinaryReader::new(data.as_slice());
+        for _ in 0..NUM_RECORDS {
+            black_box(reader.read_planned_record(black_box(&plan)).unwrap());
+        }
+    }));
+    group.finish();
+}
+
+fn records(c: &mut Criterion) {
+    bench_record(c, "fixed_record", &[
+        FieldValue::I32(-100), FieldValue::F64(727.247), FieldValue::Boolean(true), FieldValue::U16(624),
+        FieldValue::I64(-2_147_483_649), FieldValue::F32(5.2), FieldValue::U32(3_000_000_000), FieldValue::I32(-100),
+    ]);
+    bench_record(c, "mixed_record", &[
+        FieldValue::I32(-100), FieldValue::F64(727.247), FieldValue::Boolean(true), FieldValue::String("meow".to_string()),
+        FieldValue::I64(-2_147_483_649), FieldValue::F32(5.2), FieldValue::U32(3_000_000_000), FieldValue::I32(-100),
+    ]);
+}
+
+criterion_group!(benches, records);
+criterion_main!(benches);
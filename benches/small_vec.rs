@@ -0,0 +1,45 @@
+use std::hint::black_box;
+use criterion::{Criterion, criterion_group, criterion_main};
+use csharp_binary_encoding::{BinaryReader, BinaryWriter, LengthPrefix};
+use smallvec::SmallVec;
+
+const NUM_ARRAYS: usize = 1000;
+
+fn array_data(len: i32) -> Vec<u8> {
+    let mut data: Vec<u8> = Vec::new();
+    let mut writer = BinaryWriter::new(&mut data);
+    for _ in 0..NUM_ARRAYS {
+        writer.write_7_bit_encoded_int(len).unwrap();
+        for i in 0..len {
+            writer.write_i32(i).unwrap();
+        }
+    }
+    data
+}
+
+fn bench_len(c: &mut Criterion, name: &str, len: i32) {
+    let data = array_data(len);
+    let mut group = c.benchmark_group(name);
+    group.bench_function("read_array_of", |b| b.iter(|| {
+        let mut reader = BinaryReader::new(data.as_slice());
+        for _ in 0..NUM_ARRAYS {
+            black_box(reader.read_array_of::<i32>(LengthPrefix::SevenBitEncodedInt).unwrap());
+        }
+    }));
+    group.bench_function("read_vec_small", |b| b.iter(|| {
+        let mut reader = BinaryReader::new(data.as_slice());
+        for _ in 0..NUM_ARRAYS {
+            let values: SmallVec<[i32; 8]> = reader.read_vec_small(LengthPrefix::SevenBitEncodedInt, |r| r.read_i32()).unwrap();
+            black_box(values);
+        }
+    }));
+    group.finish();
+}
+
+fn arrays(c: &mut Criterion) {
+    bench_len(c, "short_arrays", 4);
+    bench_len(c, "long_arrays", 32);
+}
+
+criterion_group!(benches, arrays);
+criterion_main!(benches);
@@ -74,6 +74,14 @@ int VerifyTestBin(bool verifyf16) {
             if(!AssertEq(reader.ReadUInt32(), (uint)3000000000)) return 1;
             if(!AssertEq(reader.ReadUInt64(), (ulong)42307830165)) return 1;
             if(!AssertEq(reader.ReadChar(), '\u0000')) return 1;
+            if(!AssertEq(new TimeOnly(reader.ReadInt64()), new TimeOnly(13, 37, 0))) return 1;
+            if(!AssertEq(DateOnly.FromDayNumber(reader.ReadInt32()), new DateOnly(2025, 4, 1))) return 1;
+            if(!AssertEq(reader.ReadDecimal(), -727.247m)) return 1;
+            var guid = Guid.Parse("0f8fad5b-d9cb-469f-a165-70867728950e");
+            if(!AssertEq(new Guid(reader.ReadBytes(16)), guid)) return 1;
+            if(!AssertEq(Guid.Parse(reader.ReadString()), guid)) return 1;
+            if(!ArrayAssertEq(reader.ReadBytes(reader.ReadInt32()), new byte[] { })) return 1;
+            if(!ArrayAssertEq(reader.ReadBytes(reader.ReadInt32()), new byte[] { 0xDE, 0xAD, 0xBE, 0xEF })) return 1;
             return 0;
         }
     }
@@ -122,6 +130,21 @@ void GenerateTestBin() {
             writer.Write((ulong)42307830165);
             // write the null character
             writer.Write('\u0000');
+            // write a TimeOnly as its ticks
+            writer.Write(new TimeOnly(13, 37, 0).Ticks);
+            // write a DateOnly as its day number
+            writer.Write(new DateOnly(2025, 4, 1).DayNumber);
+            // write a decimal
+            writer.Write(-727.247m);
+            // write a Guid as its bytes and as a string
+            var guid = Guid.Parse("0f8fad5b-d9cb-469f-a165-70867728950e");
+            writer.Write(guid.ToByteArray());
+            writer.Write(guid.ToString());
+            // write byte arrays with an Int32 length
+            foreach (var array in new byte[][] { new byte[] { }, new byte[] { 0xDE, 0xAD, 0xBE, 0xEF } }) {
+                writer.Write(array.Length);
+                writer.Write(array);
+            }
         }
     }
 }
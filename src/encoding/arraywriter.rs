@@ -0,0 +1,65 @@
+use std::io::{self, Write};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+/// Writes the elements of an array whose count has already been written, and checks that
+/// exactly that many elements are written. Created by [BinaryWriter::begin_array].
+///
+/// Call [ArrayWriter::finish] once every element has been written to find out whether the count
+/// was right. Dropping an ArrayWriter without finishing it doesn't check anything, so an
+/// ArrayWriter can be abandoned when writing an element fails.
+pub struct ArrayWriter<'a, T: Write, B: ByteOrder> {
+    writer: &'a mut BinaryWriter<T, B>,
+    remaining: usize,
+    written: usize,
+}
+
+impl<T: Write, B: ByteOrder> ArrayWriter<'_, T, B> {
+    /// Returns the number of elements which still have to be written.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Writes one element with `write_element`, and returns what it returned.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] without calling `write_element` if
+    /// every declared element has already been written.
+    pub fn write_element<F, R>(&mut self, write_element: F) -> io::Result<R>
+    where F: FnOnce(&mut BinaryWriter<T, B>) -> io::Result<R> {
+        if self.remaining == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "more elements written than the array declared"));
+        }
+        let start = self.writer.num_bytes_written();
+        let result = write_element(self.writer)?;
+        self.remaining -= 1;
+        self.written += (self.writer.num_bytes_written() - start) as usize;
+        Ok(result)
+    }
+
+    /// Checks that every declared element was written, and returns the total number of bytes
+    /// written, including the count.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] if fewer elements were written than
+    /// the array declared.
+    pub fn finish(self) -> io::Result<usize> {
+        if self.remaining != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "fewer elements written than the array declared"));
+        }
+        Ok(self.written)
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Writes `count` as an Int32, like C# code
+    /// writing `array.Length` before the elements, and returns an [ArrayWriter] which the
+    /// elements are written with, so a mismatch between the count and the elements is caught.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] without writing anything if `count`
+    /// is negative.
+    pub fn begin_array(&mut self, count: i32) -> io::Result<ArrayWriter<'_, T, B>> {
+        let remaining = count.try_into().map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, "array count is negative")
+        })?;
+        let written = self.write_i32(count)?;
+        Ok(ArrayWriter { writer: self, remaining, written })
+    }
+}
@@ -1,7 +1,13 @@
-use std::io::{Read};
+use std::io::{BufRead, Cursor, Read, Seek, SeekFrom, Write};
 use std::error::Error as stdError;
 use thiserror::Error;
 use std::fmt::{Display, Formatter};
+use super::csharpreadable::CsharpReadable;
+use super::framing::{Frames, LengthPrefix, NullStringEncoding, TagEncoding};
+use super::textencoding::Encoding;
+use super::byteorder::{from_slice, to_bits, BigEndian, ByteOrder, LittleEndian, Primitive};
+use super::trace::trace_read;
+use std::marker::PhantomData;
 
 /// Indicates that an error occured while decoding the data.
 #[derive(Error, Debug)]
@@ -24,7 +30,56 @@ pub enum InvalidDataError{
     /// The underlying data overflowed the current integer type being constructed.
     IntegerOverflow,
     /// The underlaying data could not be converted to the type because it is not valid utf-8
-    InvalidUtf8
+    InvalidUtf8,
+    /// The underlying data could not be converted to the type because it is not valid utf-16.
+    InvalidUtf16,
+    /// The requested or decoded length exceeds the limit configured on the reader.
+    LengthLimitExceeded,
+    /// The data did not start with the expected magic number.
+    BadMagic,
+    /// A length or element count was negative.
+    NegativeLength,
+    /// A type code did not correspond to a supported type.
+    UnknownTypeCode,
+    /// A decoded value was outside the range of valid values for the type being constructed.
+    OutOfRange,
+    /// A decoded index referred to an entry that does not exist.
+    IndexOutOfRange,
+    /// Values were nested more deeply than the limit configured on the reader.
+    MaxDepthExceeded,
+    /// The flags of a decimal had reserved bits set or a scale greater than 28.
+    InvalidDecimal,
+    /// Compressed data could not be decompressed, or decompressed to a different length than
+    /// declared.
+    InvalidCompressedData,
+    /// A float was NaN or infinite while the reader required finite floats.
+    NonFiniteFloat,
+    /// A value did not match the constant the format requires at that position.
+    UnexpectedValue {
+        /// The value the format requires.
+        expected: i128,
+        /// The value which was read.
+        found: i128,
+    },
+    /// A string was not a Guid in its hyphenated form.
+    InvalidGuid,
+    /// The last byte a 7-bit encoded int can occupy still had its continuation bit set.
+    MalformedVarint,
+    /// A flags enum value had bits set which aren't defined flags.
+    InvalidFlags,
+    /// The number of bytes read so far didn't match the position the format requires.
+    MisalignedStream {
+        /// The position the format requires.
+        expected: u64,
+        /// The number of bytes which had been read.
+        actual: u64,
+    },
+    /// An enum discriminant didn't match any value of the enum.
+    InvalidEnumValue,
+    /// A string was not a valid absolute URI.
+    InvalidUri,
+    /// A string was not valid JSON, or didn't match the type being deserialized.
+    InvalidJson,
 }
 
 impl Display for InvalidDataError {
@@ -33,6 +88,25 @@ impl Display for InvalidDataError {
             Self::NotEnoughBytes => write!(f, "not enough bytes to decode"),
             Self::IntegerOverflow => write!(f, "decoded integer overflowed"),
             Self::InvalidUtf8 => write!(f, "data could not be decoded as valid utf8"),
+            Self::InvalidUtf16 => write!(f, "data could not be decoded as valid utf16"),
+            Self::LengthLimitExceeded => write!(f, "length exceeds the configured limit"),
+            Self::BadMagic => write!(f, "magic number did not match"),
+            Self::NegativeLength => write!(f, "decoded length was negative"),
+            Self::UnknownTypeCode => write!(f, "unknown type code"),
+            Self::OutOfRange => write!(f, "decoded value was out of range"),
+            Self::IndexOutOfRange => write!(f, "decoded index did not refer to an existing entry"),
+            Self::MaxDepthExceeded => write!(f, "nesting depth exceeds the configured limit"),
+            Self::InvalidDecimal => write!(f, "data is not a valid decimal"),
+            Self::InvalidCompressedData => write!(f, "compressed data is invalid"),
+            Self::NonFiniteFloat => write!(f, "decoded float was not finite"),
+            Self::UnexpectedValue { expected, found } => write!(f, "expected value {expected}, found {found}"),
+            Self::InvalidGuid => write!(f, "string is not a valid guid"),
+            Self::MalformedVarint => write!(f, "7-bit encoded int did not terminate"),
+            Self::InvalidFlags => write!(f, "flags value has undefined bits set"),
+            Self::InvalidEnumValue => write!(f, "value is not a valid enum discriminant"),
+            Self::InvalidUri => write!(f, "string is not a valid uri"),
+            Self::InvalidJson => write!(f, "string is not valid json for the type"),
+            Self::MisalignedStream { expected, actual } => write!(f, "expected to be at position {expected}, but {actual} bytes were read"),
         }
     }
 }
@@ -51,31 +125,90 @@ impl stdError for InvalidDataError{
     }
 }
 
+const DEFAULT_MAX_READ_LENGTH: usize = i32::MAX as usize;
+const UTF8_CHUNK_SIZE: usize = 4096;
+const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Returns the length of a utf-8 encoded character from its first byte, or `None` if the byte
+/// can't start a character.
+pub(crate) fn utf8_char_len(lead_byte: u8) -> Option<usize> {
+    match lead_byte {
+        0x00..=0x7F => Some(1),
+        0xC0..=0xDF => Some(2),
+        0xE0..=0xEF => Some(3),
+        0xF0..=0xF7 => Some(4),
+        _ => None,
+    }
+}
+
 /// Analogous to the [`System.IO.BinaryReader`] C# Class.
 ///
-/// Reads from any Read implementor. 
+/// Reads from any Read implementor. Multi-byte numeric values are read in the byte order `B`,
+/// which defaults to [LittleEndian] to match C#.
 ///
+/// The reader owns the Reader it is given, which is dropped along with it. To keep using the
+/// Reader afterward, as with the `leaveOpen` parameter of the C# constructor, pass a `&mut`
+/// reference to it instead. Note that the reader may have buffered bytes past the last value it
+/// returned; see [BinaryReader::buffered_len].
 ///
 /// [`System.IO.BinaryReader`]: <https://learn.microsoft.com/en-us/dotnet/api/system.io.binaryreader>
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
-pub struct BinaryReader<T: Read> {
+pub struct BinaryReader<T: Read, B: ByteOrder = LittleEndian> {
     input: T,
+    // bytes before `pos` have already been returned, but are kept so they can be rewound to
     buf: Vec<u8>,
-    num_bytes_read: u64
+    pos: usize,
+    num_bytes_read: u64,
+    max_read_length: usize,
+    max_total_bytes: Option<u64>,
+    max_depth: usize,
+    depth: usize,
+    interned_strings: Vec<String>,
+    strict_floats: bool,
+    tolerant_7_bit_ints: bool,
+    unsigned_string_lengths: bool,
+    null_string_encoding: NullStringEncoding,
+    encoding: Encoding,
+    byte_order: PhantomData<B>
 }
 
-/// All functions in this implementation return an error if the underlying Read returns an error,
-/// or if there aren't enough bytes to read.  Individual functions list additional error
-/// conditions.
-impl<T> BinaryReader<T> 
+/// Reads one field of every row for [BinaryReader::read_soa].
+type SoaReadColumn<'a, T, B, R> = dyn FnMut(&mut BinaryReader<T, B>, &mut R) -> Result<(), DataDecodeError> + 'a;
+
+impl<T> BinaryReader<T>
 where T: Read {
 
     ///Creates a new BinaryReader which will read data from the provided Reader.
     pub fn new(input: T) -> Self {
+        Self::with_byte_order(input)
+    }
+}
+
+/// All functions in this implementation return an error if the underlying Read returns an error,
+/// or if there aren't enough bytes to read.  Individual functions list additional error
+/// conditions.
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Creates a new BinaryReader which will read data from the provided Reader using the byte
+    /// order `B`, e.g. `BinaryReader::<_, BigEndian>::with_byte_order(input)`.
+    pub fn with_byte_order(input: T) -> Self {
         Self {
             input,
             buf: Vec::new(),
-            num_bytes_read: 0
+            pos: 0,
+            num_bytes_read: 0,
+            max_read_length: DEFAULT_MAX_READ_LENGTH,
+            max_total_bytes: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+            depth: 0,
+            interned_strings: Vec::new(),
+            strict_floats: false,
+            tolerant_7_bit_ints: false,
+            unsigned_string_lengths: false,
+            null_string_encoding: NullStringEncoding::PresenceFlag,
+            encoding: Encoding::Utf8,
+            byte_order: PhantomData
         }
     }
 
@@ -84,30 +217,339 @@ where T: Read {
         self.num_bytes_read
     }
 
+    /// Doesn't correspond to any specific c# method. Replaces the input Reader with `new_input`
+    /// and returns the old one, so one BinaryReader can be reused for many inputs. Buffered
+    /// bytes, interned strings and [BinaryReader::num_bytes_read] are cleared, but the internal
+    /// buffer keeps its capacity, and settings such as [BinaryReader::max_read_length] are kept.
+    pub fn reset(&mut self, new_input: T) -> T {
+        self.buf.clear();
+        self.pos = 0;
+        self.num_bytes_read = 0;
+        self.depth = 0;
+        self.interned_strings.clear();
+        std::mem::replace(&mut self.input, new_input)
+    }
+
+    /// Doesn't correspond to any specific c# method. Checks that [BinaryReader::num_bytes_read]
+    /// equals `expected`, to find where a parser stops following the format it reads.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::MisalignedStream]) holding both
+    /// positions if it doesn't.
+    pub fn assert_position(&self, expected: u64) -> Result<(), DataDecodeError> {
+        if self.num_bytes_read != expected {
+            return Err(InvalidDataError::MisalignedStream { expected, actual: self.num_bytes_read }.into());
+        }
+        Ok(())
+    }
+
+    /// Doesn't correspond to any specific c# method. Calls `read` and returns its result along
+    /// with the number of bytes it consumed, measured with [BinaryReader::num_bytes_read].
+    /// Useful for working out where a variable-length value ended.
+    pub fn measured<F, R>(&mut self, read: F) -> Result<(R, u64), DataDecodeError>
+    where F: FnOnce(&mut Self) -> Result<R, DataDecodeError> {
+        let start = self.num_bytes_read;
+        let result = read(self)?;
+        Ok((result, self.num_bytes_read - start))
+    }
+
+    /// Returns the maximum number of bytes a single read may request.
+    pub fn max_read_length(&self) -> usize {
+        self.max_read_length
+    }
+
+    /// Sets the maximum number of bytes a single read may request. Reads which would exceed this
+    /// limit return [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) before
+    /// anything is buffered, which protects against corrupt length prefixes. Defaults to
+    /// `i32::MAX`, the largest count C#'s ReadBytes accepts.
+    pub fn set_max_read_length(&mut self, max_read_length: usize) {
+        self.max_read_length = max_read_length;
+    }
+
+    /// Returns the maximum total number of bytes this reader will read, if one has been set.
+    pub fn max_total_bytes(&self) -> Option<u64> {
+        self.max_total_bytes
+    }
+
+    /// Sets the maximum total number of bytes this reader will read over its lifetime. Once
+    /// [BinaryReader::num_bytes_read] would exceed this budget, reads and peeks return
+    /// [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]). Useful to cap the
+    /// resources used when parsing untrusted data. Defaults to `None`, meaning no limit.
+    pub fn set_max_total_bytes(&mut self, max_total_bytes: Option<u64>) {
+        self.max_total_bytes = max_total_bytes;
+    }
+
+    /// Returns the maximum depth to which values read by nested readers such as
+    /// [BinaryReader::read_dynamic] may be nested.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Sets the maximum depth to which values read by nested readers such as
+    /// [BinaryReader::read_dynamic] may be nested. Reading a value nested more deeply returns
+    /// [DataDecodeError]::InvalidData([InvalidDataError::MaxDepthExceeded]) instead of
+    /// recursing further, which protects against overflowing the stack on untrusted data.
+    /// Defaults to 128.
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// Returns true if floats read by this reader must be finite.
+    pub fn strict_floats(&self) -> bool {
+        self.strict_floats
+    }
+
+    /// Sets whether floats read by this reader must be finite. When true, [BinaryReader::read_f32]
+    /// and [BinaryReader::read_f64] return
+    /// [DataDecodeError]::InvalidData([InvalidDataError::NonFiniteFloat]) for NaN or infinite
+    /// values, which catches corrupt or misaligned data in formats that only store finite floats.
+    /// The bytes of the float are consumed either way. Defaults to false, like C#.
+    pub fn set_strict_floats(&mut self, strict_floats: bool) {
+        self.strict_floats = strict_floats;
+    }
+
+    /// Returns true if 7-bit encoded ints are decoded tolerantly.
+    pub fn tolerant_7_bit_ints(&self) -> bool {
+        self.tolerant_7_bit_ints
+    }
+
+    /// Sets whether 7-bit encoded ints are decoded tolerantly. Some old or third-party writers
+    /// put extra bits in the last byte of a 7-bit encoded int, which C# and this reader reject by
+    /// default. When true, [BinaryReader::read_7_bit_encoded_int] and
+    /// [BinaryReader::read_7_bit_encoded_int64] never return
+    /// [InvalidDataError::IntegerOverflow] or [InvalidDataError::MalformedVarint]: they always stop
+    /// after the maximum number of bytes and keep only the bits of the last byte which fit, so
+    /// corrupt data decodes to a wrong value instead of an error. Strings and other values with a
+    /// 7-bit encoded length are affected too. Defaults to false.
+    pub fn set_tolerant_7_bit_ints(&mut self, tolerant_7_bit_ints: bool) {
+        self.tolerant_7_bit_ints = tolerant_7_bit_ints;
+    }
+
+    /// Returns true if string lengths are decoded as unsigned 7-bit encoded ints.
+    pub fn unsigned_string_lengths(&self) -> bool {
+        self.unsigned_string_lengths
+    }
+
+    /// Sets whether [BinaryReader::read_string] decodes its length prefix with
+    /// [BinaryReader::read_7_bit_encoded_uint] instead of
+    /// [BinaryReader::read_7_bit_encoded_int]. When true, a length with its highest bit set is
+    /// treated as a very large length, and returns
+    /// [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) rather than
+    /// [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]). Lengths C# can write
+    /// decode the same either way. This doesn't affect
+    /// [NullStringEncoding::SentinelLength], which needs a signed length to mark null strings.
+    /// Defaults to false, matching C#, which rejects negative lengths.
+    pub fn set_unsigned_string_lengths(&mut self, unsigned_string_lengths: bool) {
+        self.unsigned_string_lengths = unsigned_string_lengths;
+    }
+
+    /// Returns how null strings are marked for [BinaryReader::read_nullable_string].
+    pub fn null_string_encoding(&self) -> NullStringEncoding {
+        self.null_string_encoding
+    }
+
+    /// Sets how null strings are marked for [BinaryReader::read_nullable_string], to match the
+    /// writer of the data. Defaults to [NullStringEncoding::PresenceFlag].
+    pub fn set_null_string_encoding(&mut self, null_string_encoding: NullStringEncoding) {
+        self.null_string_encoding = null_string_encoding;
+    }
+
+    /// Returns the text encoding used when reading chars and strings.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Sets the text encoding used when reading chars and strings. Defaults to [Encoding::Utf8].
+    /// The byte order of this reader only affects [Encoding::Utf16], whose code units are in
+    /// that byte order; the other encodings, and the length prefix of strings, are the same in
+    /// either.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
+    /// Returns an error if strict floats are enabled and a float read wasn't finite.
+    fn check_float(&self, is_finite: bool) -> Result<(), DataDecodeError> {
+        if self.strict_floats && !is_finite {
+            return Err(InvalidDataError::NonFiniteFloat.into());
+        }
+        Ok(())
+    }
+
+    /// Runs `read_value` one level of nesting deeper, failing if that exceeds
+    /// [BinaryReader::max_depth]. Recursive readers should read each nested value through this.
+    pub(crate) fn nested<R, F>(&mut self, read_value: F) -> Result<R, DataDecodeError>
+    where F: FnOnce(&mut Self) -> Result<R, DataDecodeError> {
+        if self.depth >= self.max_depth {
+            return Err(InvalidDataError::MaxDepthExceeded.into());
+        }
+        self.depth += 1;
+        let result = read_value(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn check_read_length(&self, num_bytes: usize) -> Result<(), InvalidDataError> {
+        if num_bytes > self.max_read_length {
+            return Err(InvalidDataError::LengthLimitExceeded);
+        }
+        if let Some(max_total_bytes) = self.max_total_bytes
+            && self.num_bytes_read.saturating_add(num_bytes as u64) > max_total_bytes {
+            return Err(InvalidDataError::LengthLimitExceeded);
+        }
+        Ok(())
+    }
+
     /// Returns true if enough bytes could be allocated, false otherwise, and Err if the underlying
     /// reader returned an error.
     fn ensure_internal_buffer_size(&mut self, min_size: usize) -> Result<bool, std::io::Error>{
-        if self.buf.len() >= min_size {
+        if self.buffered().len() >= min_size {
             return Ok(true);
         }
 
         self.input.read_to_end(&mut self.buf)?;
 
-        Ok(self.buf.len() >= min_size)
+        Ok(self.buffered().len() >= min_size)
+    }
+
+    /// The bytes in the internal buffer which haven't been returned yet.
+    fn buffered(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    /// Returns the next `num_bytes` bytes from the internal buffer, which should already hold
+    /// them. If it doesn't, only the bytes it holds are returned.
+    fn take_buffered(&mut self, num_bytes: usize) -> Vec<u8> {
+        let num_bytes = num_bytes.min(self.buffered().len());
+        let bytes = self.buffered()[..num_bytes].to_vec();
+        self.pos += num_bytes;
+        bytes
     }
 
     /// Equivalent to the ReadByte method in C#. Reads one byte from the stream. 
     pub fn read_byte(&mut self) -> Result<u8, DataDecodeError> {
-        Ok(self.read_bytes(1)?[0])
+        trace_read!(self, "u8", self.next_byte())
+    }
+
+    /// Reads one byte without tracing it, for functions which trace the value they decode from it.
+    fn next_byte(&mut self) -> Result<u8, DataDecodeError> {
+        let [byte] = self.read_array()?;
+        Ok(byte)
+    }
+
+    /// Reads exactly `N` bytes into an array.
+    pub(crate) fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DataDecodeError> {
+        self.fill_buffer(N)?.try_into().map_err(|_| InvalidDataError::NotEnoughBytes.into())
+    }
+
+    /// Equivalent to the parameterless Read method in C#, which returns -1 at the end of the
+    /// stream. Reads one byte, or returns `None` without an error if there are no more bytes.
+    pub fn read_byte_or_eof(&mut self) -> Result<Option<u8>, DataDecodeError> {
+        if self.is_at_eof()? {
+            return Ok(None);
+        }
+        self.read_byte().map(Some)
+    }
+
+    /// Doesn't correspond to any specific c# method. Returns true if there are no more bytes to
+    /// read, without consuming anything.
+    pub fn is_at_eof(&mut self) -> Result<bool, DataDecodeError> {
+        Ok(!self.ensure_internal_buffer_size(1)?)
     }
 
     /// Equivalent to the ReadBytes method in C#. Reads the specified number of bytes.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) if
+    /// `num_bytes` is larger than [BinaryReader::max_read_length].
     pub fn read_bytes(&mut self, num_bytes: usize) -> Result<Vec<u8>, DataDecodeError> {
-        if num_bytes > self.buf.len() && !self.ensure_internal_buffer_size(num_bytes)? {
+        self.check_read_length(num_bytes)?;
+        if !self.ensure_internal_buffer_size(num_bytes)? {
             return Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))
         }
         self.num_bytes_read += num_bytes as u64;
-        Ok(Vec::from_iter(self.buf.drain(0..num_bytes)))
+        Ok(self.take_buffered(num_bytes))
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads an Int32 length followed by that many
+    /// bytes, as C# code commonly writes a `byte[]` with `writer.Write(bytes.Length)` followed by
+    /// `writer.Write(bytes)`.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the length is
+    /// negative, and [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) if it
+    /// is larger than [BinaryReader::max_read_length]. The length is consumed even when it is
+    /// invalid.
+    pub fn read_byte_array(&mut self) -> Result<Vec<u8>, DataDecodeError> {
+        let length = self.read_length_prefix(LengthPrefix::Int32, self.max_read_length)?;
+        self.read_bytes(length)
+    }
+
+    /// Equivalent to the FillBuffer method in C#. Reads exactly `num_bytes` bytes and returns
+    /// them borrowed from the internal buffer, without copying them into a new Vec.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NotEnoughBytes]) if there are
+    /// fewer bytes left, where C# would throw an EndOfStreamException, and
+    /// [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) if `num_bytes` is
+    /// larger than [BinaryReader::max_read_length].
+    pub fn fill_buffer(&mut self, num_bytes: usize) -> Result<&[u8], DataDecodeError> {
+        self.check_read_length(num_bytes)?;
+        if !self.ensure_internal_buffer_size(num_bytes)? {
+            return Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))
+        }
+        self.num_bytes_read += num_bytes as u64;
+        let start = self.pos;
+        self.pos += num_bytes;
+        Ok(&self.buf[start..self.pos])
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads the specified number of bytes like
+    /// [BinaryReader::read_bytes], but appends them to `output` instead of returning a new Vec,
+    /// so a buffer can be reused across reads. If there aren't enough bytes, nothing is appended
+    /// or consumed.
+    pub fn read_bytes_append(&mut self, num_bytes: usize, output: &mut Vec<u8>) -> Result<(), DataDecodeError> {
+        output.extend_from_slice(self.fill_buffer(num_bytes)?);
+        Ok(())
+    }
+    
+    /// Doesn't correspond to any specific c# method. Pushes `bytes` back onto the front of the
+    /// data stream, so the next read returns them again, and decreases
+    /// [BinaryReader::num_bytes_read] accordingly. Useful for parsers which read ahead and then
+    /// need to back off. The bytes don't have to be the ones which were read.
+    pub fn unread(&mut self, bytes: &[u8]) {
+        self.buf.splice(self.pos..self.pos, bytes.iter().copied());
+        self.num_bytes_read = self.num_bytes_read.saturating_sub(bytes.len() as u64);
+    }
+
+    /// Doesn't correspond to any specific c# method. Moves back `num_bytes` bytes within the data
+    /// this reader has already buffered, without touching the underlying Reader, so the next read
+    /// returns them again. This gives limited seeking even when the underlying Reader doesn't
+    /// implement [Seek]. Decreases [BinaryReader::num_bytes_read] accordingly.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NotEnoughBytes]) without moving
+    /// if fewer than `num_bytes` bytes have been read since the buffer was last discarded by a
+    /// seek.
+    pub fn rewind_buffered(&mut self, num_bytes: usize) -> Result<(), DataDecodeError> {
+        if num_bytes > self.pos {
+            return Err(InvalidDataError::NotEnoughBytes.into());
+        }
+        self.pos -= num_bytes;
+        self.num_bytes_read = self.num_bytes_read.saturating_sub(num_bytes as u64);
+        Ok(())
+    }
+
+    /// Doesn't correspond to any specific c# method. Moves forward `num_bytes` bytes within the
+    /// data this reader has already buffered, such as by a peek, without touching the underlying
+    /// Reader. Increases [BinaryReader::num_bytes_read] accordingly.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NotEnoughBytes]) without moving
+    /// if fewer than `num_bytes` bytes are buffered.
+    pub fn skip_buffered(&mut self, num_bytes: usize) -> Result<(), DataDecodeError> {
+        self.check_read_length(num_bytes)?;
+        if num_bytes > self.buffered().len() {
+            return Err(InvalidDataError::NotEnoughBytes.into());
+        }
+        self.pos += num_bytes;
+        self.num_bytes_read += num_bytes as u64;
+        Ok(())
+    }
+
+    /// Doesn't correspond to any specific c# method. Returns the number of bytes this reader has
+    /// taken from the underlying Reader but not returned yet, such as those read by a peek. This is
+    /// how far [BinaryReader::skip_buffered] can move, and is not the number of bytes left in the
+    /// data.
+    pub fn buffered_len(&self) -> usize {
+        self.buffered().len()
     }
     
     /// Doesn't correspond to any specific c# method. Provided for convenience. Gets the next byte
@@ -119,22 +561,47 @@ where T: Read {
     /// Doesn't correspond to any specific c# method. Provided for convenience. Gets the specified
     /// number of bytes without advancing the data stream.
     pub fn peek_bytes(&mut self, num_bytes: usize) -> Result<&[u8], DataDecodeError> {
+        self.check_read_length(num_bytes)?;
         if !self.ensure_internal_buffer_size(num_bytes)? {
             Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))
         } else {
-            Ok(&self.buf.as_slice()[0..num_bytes])
+            Ok(&self.buffered()[0..num_bytes])
+        }
+    }
+    
+    /// Doesn't correspond to any specific c# method. Reads `expected.len()` bytes and checks that
+    /// they match `expected`, as is common for the signature at the start of a file.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::BadMagic]) if they don't match.
+    /// The bytes are consumed even if they don't match.
+    pub fn expect_magic(&mut self, expected: &[u8]) -> Result<(), DataDecodeError> {
+        if self.read_bytes(expected.len())? == expected {
+            Ok(())
+        } else {
+            Err(InvalidDataError::BadMagic.into())
         }
     }
     
     /// Equivalent to the Read7BitEncodedInt method in C#.
     /// Returns [DataDecodeError]::InvalidData([InvalidDataError::IntegerOverflow]) if the encoded value does not fit within 32 bits.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::MalformedVarint]) if the last byte it can occupy still has its continuation bit set.
     /// if the integer overflows, the bytes will still be consumed.
     pub fn read_7_bit_encoded_int(&mut self) -> Result<i32, DataDecodeError> {
+        trace_read!(self, "7-bit encoded int", self.decode_7_bit_encoded_int())
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a 7-bit encoded int like
+    /// [BinaryReader::read_7_bit_encoded_int], but interprets its 32 bits as unsigned, which is
+    /// how C# decodes them before casting to an Int32. Returns the same errors.
+    pub fn read_7_bit_encoded_uint(&mut self) -> Result<u32, DataDecodeError> {
+        trace_read!(self, "7-bit encoded uint", self.decode_7_bit_encoded_int().map(|value| value as u32))
+    }
+
+    fn decode_7_bit_encoded_int(&mut self) -> Result<i32, DataDecodeError> {
         const MAX_BYTES: u32 = 5;
         let mut output: i32 = 0;
         let mut bytes_read = 0;
         loop {
-            let byte =  self.read_byte()?;
+            let byte =  self.next_byte()?;
             let lower_bits = byte & 0b01111111;
             let high_bit = byte & 0b10000000;
             output += (lower_bits as i32) << (7 * bytes_read);
@@ -148,23 +615,64 @@ where T: Read {
         }
         
         let max_value_for_most_significant_bit = u8::pow(2, 32 - 28) - 1;
-        let last_byte: u8 = self.read_byte()?;
-        if last_byte > max_value_for_most_significant_bit {
+        let last_byte: u8 = self.next_byte()?;
+        if self.tolerant_7_bit_ints {
+            // the bits which don't fit are shifted out
+            Ok(output + ((last_byte as i32) << 28_i32))
+        } else if last_byte & 0b10000000 != 0 {
+            Err(DataDecodeError::InvalidData(InvalidDataError::MalformedVarint))
+        } else if last_byte > max_value_for_most_significant_bit {
             Err(DataDecodeError::InvalidData(InvalidDataError::IntegerOverflow))
         } else {
             Ok(output + ((last_byte as i32) << 28_i32))
         }
     }
     
+    /// Doesn't correspond to any specific c# method. Returns the number of bytes the next 7-bit
+    /// encoded int occupies, as would be consumed by [BinaryReader::read_7_bit_encoded_int],
+    /// without advancing the data stream.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::IntegerOverflow]) if the encoded
+    /// value does not fit within 32 bits,
+    /// [DataDecodeError]::InvalidData([InvalidDataError::MalformedVarint]) if its last byte still
+    /// has the continuation bit set, and
+    /// [DataDecodeError]::InvalidData([InvalidDataError::NotEnoughBytes]) if the data ends before
+    /// the encoded value does.
+    pub fn peek_7_bit_encoded_int_len(&mut self) -> Result<usize, DataDecodeError> {
+        const MAX_BYTES: usize = 5;
+        for len in 1..MAX_BYTES {
+            if self.peek_bytes(len)?[len - 1] & 0b10000000 == 0 {
+                return Ok(len);
+            }
+        }
+        let max_value_for_most_significant_bit = u8::pow(2, 32 - 28) - 1;
+        let last_byte = self.peek_bytes(MAX_BYTES)?[MAX_BYTES - 1];
+        if self.tolerant_7_bit_ints {
+            Ok(MAX_BYTES)
+        } else if last_byte & 0b10000000 != 0 {
+            Err(DataDecodeError::InvalidData(InvalidDataError::MalformedVarint))
+        } else if last_byte > max_value_for_most_significant_bit {
+            Err(DataDecodeError::InvalidData(InvalidDataError::IntegerOverflow))
+        } else {
+            Ok(MAX_BYTES)
+        }
+    }
+
     /// Equivalent to the Read7BitEncodedInt64 method in C#.
     /// Returns [DataDecodeError]::InvalidData([InvalidDataError::IntegerOverflow]) if the encoded value does not fit within 64 bits.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::MalformedVarint]) if the last byte it can occupy still has its continuation bit set.
     /// if the integer overflows, the bytes will still be consumed
     pub fn read_7_bit_encoded_int64(&mut self) -> Result<i64, DataDecodeError> {
+        trace_read!(self, "7-bit encoded int64", self.decode_7_bit_encoded_int64())
+    }
+
+    fn decode_7_bit_encoded_int64(&mut self) -> Result<i64, DataDecodeError> {
         const MAX_BYTES: u32 = 10;
         let mut output: i64 = 0; 
         let mut bytes_read = 0;
         loop {
-            let byte: u8 = self.read_byte()?;
+            let byte: u8 = self.next_byte()?;
             let lower_bits = byte & 0b01111111;
             let high_bit = byte & 0b10000000;
             output += (lower_bits as i64) << (7 * bytes_read);
@@ -178,114 +686,488 @@ where T: Read {
         }
 
         let max_value_for_most_significant_bit = u8::pow(2, 64 - 63) - 1;
-        let last_byte = self.read_byte()?;
-        if last_byte > max_value_for_most_significant_bit {
+        let last_byte = self.next_byte()?;
+        if self.tolerant_7_bit_ints {
+            Ok(output + ((last_byte as i64) << 63))
+        } else if last_byte & 0b10000000 != 0 {
+            Err(InvalidDataError::MalformedVarint.into())
+        } else if last_byte > max_value_for_most_significant_bit {
             Err(InvalidDataError::IntegerOverflow.into())
         } else {
             Ok(output + ((last_byte as i64) << 63))
         }
     }
     
+    /// Reads a fixed-size numeric value in this reader's byte order.
+    fn read_primitive<N: Primitive>(&mut self) -> Result<N, DataDecodeError> {
+        self.read_primitive_in::<N, B>()
+    }
+
+    /// Reads a fixed-size numeric value in the byte order `O`, regardless of this reader's.
+    fn read_primitive_in<N: Primitive, O: ByteOrder>(&mut self) -> Result<N, DataDecodeError> {
+        trace_read!(self, std::any::type_name::<N>(), self.fill_buffer(size_of::<N::Bytes>()).and_then(|read| {
+            from_slice::<N, O>(read).ok_or_else(|| InvalidDataError::NotEnoughBytes.into())
+        }))
+    }
+    
+    /// Doesn't correspond to any specific c# method. Reads a zigzag encoded varint, as used by
+    /// Protocol Buffers' `sint32`.
+    ///
+    /// Zigzag encoding maps signed values to unsigned ones so that values close to zero, including
+    /// negative ones, are small: 0, -1, 1, -2 become 0, 1, 2, 3. The result is then 7-bit encoded.
+    /// This differs from [BinaryReader::read_7_bit_encoded_int], which reads the two's complement
+    /// bit pattern directly, so any negative value takes the full 5 bytes.
+    pub fn read_zigzag_varint32(&mut self) -> Result<i32, DataDecodeError> {
+        let encoded = self.read_7_bit_encoded_int()? as u32;
+        Ok((encoded >> 1) as i32 ^ -((encoded & 1) as i32))
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a zigzag encoded varint, as used by
+    /// Protocol Buffers' `sint64`. See [BinaryReader::read_zigzag_varint32] for how this differs
+    /// from [BinaryReader::read_7_bit_encoded_int64].
+    pub fn read_zigzag_varint64(&mut self) -> Result<i64, DataDecodeError> {
+        let encoded = self.read_7_bit_encoded_int64()? as u64;
+        Ok((encoded >> 1) as i64 ^ -((encoded & 1) as i64))
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a signed LEB128 value, as used by
+    /// DWARF and WebAssembly.
+    ///
+    /// Signed LEB128 sign-extends the value from bit 6 of its last byte, so small negative values
+    /// are as short as small positive ones: -1 is the single byte `0x7F`. This differs from
+    /// [BinaryReader::read_7_bit_encoded_int], which reads the two's complement bit pattern
+    /// directly, so it reads `0x7F` as 127 and any negative value takes the full 5 bytes.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::IntegerOverflow]) if the encoded
+    /// value does not fit within 32 bits. If it doesn't, the bytes will still be consumed.
+    pub fn read_leb128_i32(&mut self) -> Result<i32, DataDecodeError> {
+        i32::try_from(self.read_leb128(5)?).map_err(|_| InvalidDataError::IntegerOverflow.into())
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a signed LEB128 value which fits
+    /// within 64 bits. See [BinaryReader::read_leb128_i32] for how this differs from
+    /// [BinaryReader::read_7_bit_encoded_int64].
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::IntegerOverflow]) if the encoded
+    /// value does not fit within 64 bits. If it doesn't, the bytes will still be consumed.
+    pub fn read_leb128_i64(&mut self) -> Result<i64, DataDecodeError> {
+        self.read_leb128(10)
+    }
+
+    /// Reads at most `max_bytes` bytes of signed LEB128, sign-extending the result to 64 bits.
+    fn read_leb128(&mut self, max_bytes: u32) -> Result<i64, DataDecodeError> {
+        let mut output: i64 = 0;
+        for index in 0..max_bytes {
+            let byte = self.read_byte()?;
+            let shift = 7 * index;
+            if shift == 63 {
+                // only the sign bit is left, so the rest of the byte has to match it
+                return match byte {
+                    0x00 => Ok(output),
+                    0x7F => Ok(output | i64::MIN),
+                    _ => Err(InvalidDataError::IntegerOverflow.into()),
+                };
+            }
+            output |= i64::from(byte & 0b01111111) << shift;
+            if byte & 0b10000000 == 0 {
+                if byte & 0b01000000 != 0 {
+                    output |= -1 << (shift + 7);
+                }
+                return Ok(output);
+            }
+        }
+        Err(InvalidDataError::IntegerOverflow.into())
+    }
+    
     /// Equivalent to the ReadBoolean method in C#.
     pub fn read_boolean(&mut self) -> Result<bool, DataDecodeError> {
+        trace_read!(self, "bool", self.next_byte().map(|byte| byte != 0))
+    }
+    
+    /// Doesn't correspond to any specific c# method. Reads a boolean like
+    /// [BinaryReader::read_boolean], but also returns the byte it was decoded from, which is
+    /// useful for diagnosing misaligned reads.
+    pub fn read_boolean_raw(&mut self) -> Result<(bool, u8), DataDecodeError> {
         let byte = self.read_byte()?;
-        Ok(byte != 0)
+        Ok((byte != 0, byte))
+    }
+    
+    /// Doesn't correspond to any specific c# method. Reads one byte and unpacks it into eight
+    /// flags. The bit order is least significant bit first, so `flags[0]` is `byte & 0x01` and
+    /// `flags[7]` is `byte & 0x80`, matching how C# code usually packs flags with `1 << index`.
+    pub fn read_flags(&mut self) -> Result<[bool; 8], DataDecodeError> {
+        let byte = self.read_byte()?;
+        Ok(std::array::from_fn(|index| byte & (1 << index) != 0))
+    }
+    
+    /// Doesn't correspond to any specific c# method. Reads the one byte marker many .NET
+    /// serializers write before a reference type, where 0 means null and anything else means a
+    /// value follows. Returns true if a value follows.
+    pub fn read_is_present(&mut self) -> Result<bool, DataDecodeError> {
+        self.read_boolean()
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a marker as read by
+    /// [BinaryReader::read_is_present], followed by a value read with `read_value` if the marker
+    /// says one is present.
+    pub fn read_optional<R, F>(&mut self, read_value: F) -> Result<Option<R>, DataDecodeError>
+    where F: FnOnce(&mut Self) -> Result<R, DataDecodeError> {
+        if self.read_is_present()? {
+            read_value(self).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Doesn't correspond to any specific c# method. Reads a C# `[Flags]` enum value, which is
+    /// written as its underlying integer type `N`, e.g. `i32` for an enum with no explicit type.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidFlags]) if any bits
+    /// outside `valid_mask` are set, which catches corrupt data or flags added by a newer version
+    /// of the format. The bytes are consumed either way.
+    pub fn read_flags_enum<N: Primitive>(&mut self, valid_mask: u64) -> Result<N, DataDecodeError> {
+        let value: N = self.read_primitive()?;
+        if to_bits(value) & !valid_mask != 0 {
+            return Err(InvalidDataError::InvalidFlags.into());
+        }
+        Ok(value)
     }
     
     /// Equivalent to the ReadSingle method in C#.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NonFiniteFloat]) if the value
+    /// isn't finite and [BinaryReader::strict_floats] is set.
     pub fn read_f32(&mut self) -> Result<f32, DataDecodeError> {
-        let bytes: [u8; 4] = self.read_bytes(4)?.try_into().unwrap();
-        Ok(f32::from_le_bytes(bytes))
+        let value: f32 = self.read_primitive()?;
+        self.check_float(value.is_finite())?;
+        Ok(value)
     }
 
     /// Equivalent to the ReadDouble method in C#.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NonFiniteFloat]) if the value
+    /// isn't finite and [BinaryReader::strict_floats] is set.
     pub fn read_f64(&mut self) -> Result<f64, DataDecodeError> {
-        let bytes: [u8; 8] = self.read_bytes(8)?.try_into().unwrap();
-        Ok(f64::from_le_bytes(bytes))
+        let value: f64 = self.read_primitive()?;
+        self.check_float(value.is_finite())?;
+        Ok(value)
     }
     
     /// Equivalent to the ReadHalf method in C#.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NonFiniteFloat]) if the value
+    /// isn't finite and [BinaryReader::strict_floats] is set.
     /// Requires the `f16` feature.
     #[cfg_attr(docsrs, doc(cfg(feature = "f16")))]
     #[cfg(feature = "f16")]
     pub fn read_f16(&mut self) -> Result<f16, DataDecodeError> {
-        let bytes: [u8; 2] = self.read_bytes(2)?.try_into().unwrap();
-        Ok(f16::from_le_bytes(bytes))
+        let value: f16 = self.read_primitive()?;
+        self.check_float(value.is_finite())?;
+        Ok(value)
     }
     
-    /// Equivalent to the ReadString method in C#.
+    /// Equivalent to the ReadString method in C#. The length prefix is the number of bytes,
+    /// which are decoded using the reader's [Encoding].
     /// Returns an [DataDecodeError]::InvalidData([InvalidDataError::InvalidUtf8]) if the data read is not valid utf-8.
+    /// Utf-8 strings are validated incrementally as they are read, so invalid data is reported
+    /// without first reading the whole declared length.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidUtf16]) under
+    /// any of the utf-16 encodings if the data is not valid utf-16.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the length
+    /// prefix is negative, where C# would throw an IOException, unless
+    /// [BinaryReader::unsigned_string_lengths] is set.
     /// This function can consume some bytes even when it fails.
     pub fn read_string(&mut self) -> Result<String, DataDecodeError> {
-        let length: usize = self.read_7_bit_encoded_int()?.try_into().unwrap();
-        let string_bytes = self.read_bytes(length)?;
-        match std::str::from_utf8(string_bytes.as_slice()) {
-            Ok(v) => Ok(v.to_string()),
-            Err(_) => Err(DataDecodeError::InvalidData(InvalidDataError::InvalidUtf8))
+        trace_read!(self, "string", self.read_string_length()
+            .and_then(|length| self.read_string_body(length)))
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a string written like
+    /// [BinaryReader::read_string] and writes its utf-8 bytes to `out` as they are read and
+    /// validated, without building the whole string. Returns the number of bytes written to
+    /// `out`, which is the length of the string. The string is always decoded as utf-8,
+    /// regardless of [BinaryReader::encoding].
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidUtf8]) if the data is not
+    /// valid utf-8, in which case the valid part before the chunk containing the invalid data
+    /// has already been written to `out`. Returns the same length errors as
+    /// [BinaryReader::read_string], and [DataDecodeError::IO] if writing to `out` fails.
+    pub fn pipe_string<W: Write + ?Sized>(&mut self, out: &mut W) -> Result<usize, DataDecodeError> {
+        let length = self.read_string_length()?;
+        self.stream_utf8(length, |valid| Ok(out.write_all(valid.as_bytes())?))?;
+        Ok(length)
+    }
+
+    /// Reads the 7-bit encoded length of a string, as specified by
+    /// [BinaryReader::unsigned_string_lengths].
+    pub(crate) fn read_string_length(&mut self) -> Result<usize, DataDecodeError> {
+        if !self.unsigned_string_lengths {
+            return self.read_length_prefix(LengthPrefix::SevenBitEncodedInt, self.max_read_length);
+        }
+        let length: usize = self.read_7_bit_encoded_uint()?.try_into().map_err(|_| InvalidDataError::LengthLimitExceeded)?;
+        if length > self.max_read_length {
+            return Err(InvalidDataError::LengthLimitExceeded.into());
+        }
+        Ok(length)
+    }
+
+    /// Reads `length` bytes of a string in the reader's [Encoding].
+    fn read_string_body(&mut self, length: usize) -> Result<String, DataDecodeError> {
+        match self.encoding {
+            Encoding::Utf8 => self.read_utf8(length),
+            encoding => Ok(encoding.decode::<B>(self.read_bytes(length)?)?),
+        }
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a string which may be null, marked as
+    /// specified by [BinaryReader::null_string_encoding]. The string itself is read like
+    /// [BinaryReader::read_string], and returns the same errors.
+    /// Under [NullStringEncoding::SentinelLength], a negative length other than -1 returns
+    /// [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]).
+    pub fn read_nullable_string(&mut self) -> Result<Option<String>, DataDecodeError> {
+        match self.null_string_encoding {
+            NullStringEncoding::PresenceFlag => self.read_optional(|reader| reader.read_string()),
+            NullStringEncoding::SentinelLength => {
+                let length = self.read_7_bit_encoded_int()?;
+                if length == -1 {
+                    return Ok(None);
+                }
+                let length: usize = length.try_into().map_err(|_| InvalidDataError::NegativeLength)?;
+                self.check_read_length(length)?;
+                self.read_string_body(length).map(Some)
+            },
+        }
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a UInt32 number of bytes, followed by
+    /// that many bytes of utf-8, as some serializers write strings instead of using a 7-bit
+    /// encoded length.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidUtf8]) if the data is not
+    /// valid utf-8, and [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) if
+    /// the length is larger than [BinaryReader::max_read_length].
+    /// This function can consume some bytes even when it fails.
+    pub fn read_string_u32_prefixed(&mut self) -> Result<String, DataDecodeError> {
+        let length = self.read_length_prefix(LengthPrefix::UInt32, self.max_read_length)?;
+        self.read_utf8(length)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a number of characters encoded as
+    /// specified by `prefix`, followed by that many utf-8 encoded characters, for formats which
+    /// count characters rather than bytes.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidUtf8]) if the data is not
+    /// valid utf-8, [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the count
+    /// is negative, and [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) if
+    /// it is larger than [BinaryReader::max_read_length].
+    /// This function can consume some bytes even when it fails.
+    pub fn read_string_by_char_count(&mut self, prefix: LengthPrefix) -> Result<String, DataDecodeError> {
+        let count = self.read_length_prefix(prefix, self.max_read_length)?;
+        let mut output = String::new();
+        for _ in 0..count {
+            let length = utf8_char_len(self.peek_byte()?).ok_or(InvalidDataError::InvalidUtf8)?;
+            let bytes = self.read_bytes(length)?;
+            output.push_str(std::str::from_utf8(&bytes).map_err(|_| InvalidDataError::InvalidUtf8)?);
+        }
+        Ok(output)
+    }
+
+    /// Reads `length` bytes of utf-8 in chunks, validating each chunk as it arrives. A multi-byte
+    /// character split across two chunks is carried over to the next one. Consumes the bytes
+    /// read so far when it fails, so [BinaryReader::num_bytes_read] points just past the chunk
+    /// containing the invalid data.
+    fn read_utf8(&mut self, length: usize) -> Result<String, DataDecodeError> {
+        let mut output = String::new();
+        self.stream_utf8(length, |valid| {
+            output.push_str(valid);
+            Ok(())
+        })?;
+        Ok(output)
+    }
+
+    /// Reads `length` bytes of utf-8 like [BinaryReader::read_utf8], passing each validated part
+    /// to `sink` as soon as it has been read instead of collecting them.
+    fn stream_utf8<F>(&mut self, length: usize, mut sink: F) -> Result<(), DataDecodeError>
+    where F: FnMut(&str) -> Result<(), DataDecodeError> {
+        self.check_read_length(length)?;
+        let mut pending: Vec<u8> = Vec::new();
+        let mut remaining = length;
+        while remaining > 0 {
+            let chunk_len = remaining.min(UTF8_CHUNK_SIZE);
+            self.ensure_internal_buffer_size(chunk_len)?;
+            let available = chunk_len.min(self.buffered().len());
+            pending.extend(self.take_buffered(available));
+            self.num_bytes_read += available as u64;
+            remaining -= available;
+
+            let valid = match std::str::from_utf8(&pending) {
+                Ok(valid) => valid,
+                // the chunk ends partway through a character, keep the rest for the next chunk
+                Err(e) if e.error_len().is_none() => std::str::from_utf8(&pending[0..e.valid_up_to()])
+                    .map_err(|_| InvalidDataError::InvalidUtf8)?,
+                Err(_) => return Err(InvalidDataError::InvalidUtf8.into()),
+            };
+            sink(valid)?;
+            let num_valid = valid.len();
+            pending.drain(0..num_valid);
+
+            if available < chunk_len {
+                return Err(InvalidDataError::NotEnoughBytes.into());
+            }
+        }
+        if pending.is_empty() {
+            Ok(())
+        } else {
+            Err(InvalidDataError::InvalidUtf8.into()) // ended partway through a character
+        }
+    }
+    
+    /// Doesn't correspond to any specific c# method. Reads a string written by
+    /// [BinaryWriter::write_interned_string](crate::BinaryWriter::write_interned_string).
+    /// A 7-bit encoded int is read first. If it is 0, a string follows as in
+    /// [BinaryReader::read_string] and is added to the end of this reader's string table.
+    /// Otherwise it is one more than the index of a string already in the table.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::IndexOutOfRange]) if the index
+    /// is negative or not in the table.
+    pub fn read_interned_string(&mut self) -> Result<String, DataDecodeError> {
+        match self.read_7_bit_encoded_int()? {
+            0 => {
+                let data = self.read_string()?;
+                self.interned_strings.push(data.clone());
+                Ok(data)
+            },
+            marker => usize::try_from(marker).ok()
+                .and_then(|marker| self.interned_strings.get(marker - 1))
+                .cloned()
+                .ok_or(InvalidDataError::IndexOutOfRange.into()),
+        }
+    }
+
+    /// Forgets every string read by [BinaryReader::read_interned_string], so the next interned
+    /// string read is expected to be written in full. Use this where the writer's table was
+    /// cleared.
+    pub fn clear_interned_strings(&mut self) {
+        self.interned_strings.clear();
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a null-terminated utf-8 string, as used
+    /// by C and by strings marshalled with P/Invoke. The terminator is consumed but not included
+    /// in the returned string.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NotEnoughBytes]) if the data
+    /// ends before a terminator is found, and
+    /// [DataDecodeError]::InvalidData([InvalidDataError::InvalidUtf8]) if the string is not valid
+    /// utf-8. This function can consume some bytes even when it fails.
+    pub fn read_cstring(&mut self) -> Result<String, DataDecodeError> {
+        let bytes = self.read_until(0)?;
+        String::from_utf8(bytes).map_err(|_| InvalidDataError::InvalidUtf8.into())
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads bytes up to the first occurrence of
+    /// `terminator`, for formats which delimit data with a terminator byte rather than a length.
+    /// The terminator is consumed but not included in the returned bytes.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NotEnoughBytes]) if the data
+    /// ends before a terminator is found, in which case the bytes read are consumed.
+    pub fn read_until(&mut self, terminator: u8) -> Result<Vec<u8>, DataDecodeError> {
+        let mut bytes: Vec<u8> = Vec::new();
+        loop {
+            let byte = self.read_byte()?;
+            if byte == terminator {
+                return Ok(bytes);
+            }
+            bytes.push(byte);
         }
     }
     
     /// Equivalent to the ReadSByte method in C#.
     pub fn read_i8(&mut self) -> Result<i8, DataDecodeError> {
-        let bytes: [u8; 1] = [self.read_byte()?];
-        Ok(i8::from_le_bytes(bytes))
+        trace_read!(self, "i8", self.next_byte().map(|byte| i8::from_le_bytes([byte])))
     }
 
     /// Equivalent to the ReadInt16 method in C#.
     pub fn read_i16(&mut self) -> Result<i16, DataDecodeError> {
-        let bytes: [u8; 2] = self.read_bytes(2)?.try_into().unwrap();
-        Ok(i16::from_le_bytes(bytes))
+        self.read_primitive()
     }
 
     /// Equivalent to the ReadInt32 method in C#.
     pub fn read_i32(&mut self) -> Result<i32, DataDecodeError> {
-        let bytes: [u8; 4] = self.read_bytes(4)?.try_into().unwrap();
-        Ok(i32::from_le_bytes(bytes))
+        self.read_primitive()
     }
 
     /// Equivalent to the ReadInt64 method in C#.
     pub fn read_i64(&mut self) -> Result<i64, DataDecodeError> {
-        let bytes: [u8; 8] = self.read_bytes(8)?.try_into().unwrap();
-        Ok(i64::from_le_bytes(bytes))
+        self.read_primitive()
     }
 
     /// Equivalent to the ReadUint16 method in C#.
     pub fn read_u16(&mut self) -> Result<u16, DataDecodeError> {
-        let bytes: [u8; 2] = self.read_bytes(2)?.try_into().unwrap();
-        Ok(u16::from_le_bytes(bytes))
+        self.read_primitive()
     }
 
     /// Equivalent to the ReadUint32 method in C#.
     pub fn read_u32(&mut self) -> Result<u32, DataDecodeError> {
-        let bytes: [u8; 4] = self.read_bytes(4)?.try_into().unwrap();
-        Ok(u32::from_le_bytes(bytes))
+        self.read_primitive()
     }
 
     /// Equivalent to the ReadUint64 method in C#.
     pub fn read_u64(&mut self) -> Result<u64, DataDecodeError> {
-        let bytes: [u8; 8] = self.read_bytes(8)?.try_into().unwrap();
-        Ok(u64::from_le_bytes(bytes))
+        self.read_primitive()
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a UInt64 and converts it to a usize,
+    /// so lengths and indices can be stored with the same width on every platform.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::IntegerOverflow]) if the value
+    /// doesn't fit in a usize on the current platform. The bytes are consumed even if it doesn't.
+    pub fn read_u64_as_usize(&mut self) -> Result<usize, DataDecodeError> {
+        usize::try_from(self.read_u64()?).map_err(|_| InvalidDataError::IntegerOverflow.into())
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads an Int64 and converts it to an isize,
+    /// so offsets can be stored with the same width on every platform.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::IntegerOverflow]) if the value
+    /// doesn't fit in an isize on the current platform. The bytes are consumed even if it doesn't.
+    pub fn read_i64_as_isize(&mut self) -> Result<isize, DataDecodeError> {
+        isize::try_from(self.read_i64()?).map_err(|_| InvalidDataError::IntegerOverflow.into())
     }
     
     // Implementation translated from the c# dotnet runtime's implementation of BinaryReader
     // MIT Licensed by the .NET foundation, can be found at https://github.com/dotnet/runtime
-    /// Equivalent to the ReadChar method in C#.
+    /// Equivalent to the ReadChar method in C#. Uses the reader's [Encoding]; under
+    /// the utf-16 encodings a surrogate pair is combined into one char.
     /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidUtf8]) if the next character is not a valid character in
-    /// utf-8
+    /// utf-8, and [DataDecodeError]::InvalidData([InvalidDataError::InvalidUtf16]) if it is an
+    /// unpaired surrogate in utf-16.
     /// this function can consume some bytes even when it fails.
     pub fn read_char(&mut self) -> Result<char, DataDecodeError> {
+        trace_read!(self, "char", match self.encoding {
+            Encoding::Utf8 => self.decode_utf8_char(),
+            Encoding::Utf16 => self.decode_utf16_char::<B>(),
+            Encoding::Utf16BigEndian => self.decode_utf16_char::<BigEndian>(),
+            Encoding::Utf16LittleEndian => self.decode_utf16_char::<LittleEndian>(),
+            Encoding::Latin1 => self.next_byte().map(char::from),
+        })
+    }
+
+    fn decode_utf16_char<O: ByteOrder>(&mut self) -> Result<char, DataDecodeError> {
+        let mut units: Vec<u16> = Vec::with_capacity(2);
+        loop {
+            units.push(O::from_bytes(self.read_array()?));
+            // a high surrogate is followed by the low surrogate which completes the pair
+            if units.len() == 2 || !(0xD800..0xDC00).contains(&units[0]) {
+                break;
+            }
+        }
+        match char::decode_utf16(units).next() {
+            Some(Ok(c)) => Ok(c),
+            _ => Err(InvalidDataError::InvalidUtf16.into()),
+        }
+    }
+
+    fn decode_utf8_char(&mut self) -> Result<char, DataDecodeError> {
         const MAX_BYTES_PER_CHAR: usize = 4;
         let mut bytes: [u8; MAX_BYTES_PER_CHAR] = [0; MAX_BYTES_PER_CHAR];
         let mut current_index: usize = 0;
         let mut num_chars_read: usize = 0;
         let mut decode_result: Result<String, std::string::FromUtf8Error>;
         loop { 
-            bytes[current_index] = self.read_byte()?;
+            bytes[current_index] = self.next_byte()?;
             decode_result = String::from_utf8(bytes.to_vec());
             if let Ok(result) = &decode_result {
                 let mut result = result.as_str();
                 // trim null bytes, but always keep at least one byte
-                while result.chars().last() == Some(char::from(0)) && result.chars().collect::<Vec<_>>().len() > 1 {
+                while result.ends_with('\0') && result.chars().count() > 1 {
                     result = &result[0..result.len() - 1]; 
                 }
                 num_chars_read = result.chars().count();
@@ -297,12 +1179,491 @@ where T: Read {
                 }
             }
         }
-        if num_chars_read == 1 {
-            if let Ok(result) = decode_result {
-                return Ok(result.chars().next().expect("?"))
-            } 
-        } 
+        if num_chars_read == 1 && let Ok(result) = decode_result && let Some(c) = result.chars().next() {
+            return Ok(c)
+        }
         Err(DataDecodeError::InvalidData(InvalidDataError::InvalidUtf8)) // read two chars somehow
     }
+
+    /// Doesn't correspond to any specific c# method. Reads a type tag encoded as specified by
+    /// `encoding`, then calls `read_payload` with the tag so it can read the matching payload.
+    /// This mirrors the common C# idiom of writing a type tag before a concrete type's fields.
+    pub fn read_tagged<F, R>(&mut self, encoding: TagEncoding, read_payload: F) -> Result<R, DataDecodeError>
+    where F: FnOnce(i32, &mut Self) -> Result<R, DataDecodeError> {
+        let tag = match encoding {
+            TagEncoding::Byte => self.read_byte()? as i32,
+            TagEncoding::Int32 => self.read_i32()?,
+            TagEncoding::SevenBitEncodedInt => self.read_7_bit_encoded_int()?,
+        };
+        read_payload(tag, self)
+    }
+
+    /// Reads a length or element count encoded as specified by `prefix`.
+    fn read_length(&mut self, prefix: LengthPrefix) -> Result<usize, DataDecodeError> {
+        let length: i64 = match prefix {
+            LengthPrefix::SevenBitEncodedInt => self.read_7_bit_encoded_int()?.into(),
+            LengthPrefix::Int32 => self.read_i32()?.into(),
+            LengthPrefix::UInt16 => self.read_u16()?.into(),
+            LengthPrefix::UInt32 => self.read_u32()?.into(),
+        };
+        length.try_into().map_err(|_| InvalidDataError::NegativeLength.into())
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a length or element count encoded as
+    /// specified by `kind`, and checks that it can be used as a count of at most `max`.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if it is
+    /// negative, and [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) if it
+    /// is greater than `max`. The prefix is consumed even when it is invalid.
+    pub fn read_length_prefix(&mut self, kind: LengthPrefix, max: usize) -> Result<usize, DataDecodeError> {
+        let length = self.read_length(kind)?;
+        if length > max {
+            return Err(InvalidDataError::LengthLimitExceeded.into());
+        }
+        Ok(length)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a count encoded as specified by
+    /// `prefix`, followed by that many key/value pairs, as C# code commonly writes a
+    /// `Dictionary<K, V>`. The pairs are returned in the order they were read, so the caller can
+    /// collect them into any map type and decide how to handle duplicate keys.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the count is
+    /// negative.
+    pub fn read_map<K, V, FK, FV>(&mut self, prefix: LengthPrefix, mut read_key: FK, mut read_value: FV) -> Result<Vec<(K, V)>, DataDecodeError>
+    where FK: FnMut(&mut Self) -> Result<K, DataDecodeError>, FV: FnMut(&mut Self) -> Result<V, DataDecodeError> {
+        let count = self.read_length(prefix)?;
+        let mut pairs = Vec::new();
+        for _ in 0..count {
+            let key = read_key(self)?;
+            let value = read_value(self)?;
+            pairs.push((key, value));
+        }
+        Ok(pairs)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a sparse array of `total_len`
+    /// elements, written as an Int32 count of populated elements followed by that many Int32
+    /// index and value pairs. Elements which aren't written are `None`. If an index is written
+    /// more than once, the last value is kept.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::IndexOutOfRange]) if an index
+    /// is negative or not less than `total_len`, and
+    /// [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the count is
+    /// negative.
+    pub fn read_sparse<R, F>(&mut self, total_len: usize, mut read_value: F) -> Result<Vec<Option<R>>, DataDecodeError>
+    where F: FnMut(&mut Self) -> Result<R, DataDecodeError> {
+        let count = self.read_length(LengthPrefix::Int32)?;
+        let mut values: Vec<Option<R>> = std::iter::repeat_with(|| None).take(total_len).collect();
+        for _ in 0..count {
+            let index: usize = self.read_i32()?.try_into().map_err(|_| InvalidDataError::IndexOutOfRange)?;
+            let value = read_value(self)?;
+            *values.get_mut(index).ok_or(InvalidDataError::IndexOutOfRange)? = Some(value);
+        }
+        Ok(values)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads key/value pairs until the end of the
+    /// data, for data written pair by pair with no count. This complements
+    /// [BinaryReader::read_map].
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NotEnoughBytes]) if the data ends
+    /// partway through a pair, rather than returning the pairs read before it.
+    pub fn read_pairs_until_eof<K, V, FK, FV>(&mut self, mut read_key: FK, mut read_value: FV) -> Result<Vec<(K, V)>, DataDecodeError>
+    where FK: FnMut(&mut Self) -> Result<K, DataDecodeError>, FV: FnMut(&mut Self) -> Result<V, DataDecodeError> {
+        let mut pairs = Vec::new();
+        while !self.is_at_eof()? {
+            let key = read_key(self)?;
+            let value = read_value(self)?;
+            pairs.push((key, value));
+        }
+        Ok(pairs)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads `count` rows stored column by column
+    /// (all values of the first field, then all values of the second, and so on), as written by
+    /// [BinaryWriter::write_soa](crate::BinaryWriter::write_soa). Each row starts as
+    /// `R::default()`, and each entry of `columns` reads one value into the given row. Every
+    /// column is read fully before the next one is started.
+    pub fn read_soa<R: Default>(&mut self, count: usize, columns: &mut [&mut SoaReadColumn<'_, T, B, R>]) -> Result<Vec<R>, DataDecodeError> {
+        let mut rows: Vec<R> = std::iter::repeat_with(R::default).take(count).collect();
+        for column in columns.iter_mut() {
+            for row in rows.iter_mut() {
+                column(self, row)?;
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a count encoded as specified by
+    /// `prefix`, followed by that many strings as read by [BinaryReader::read_string], as C# code
+    /// commonly writes a `string[]`. Each string is checked against
+    /// [BinaryReader::max_read_length].
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the count is
+    /// negative.
+    pub fn read_string_array(&mut self, prefix: LengthPrefix) -> Result<Vec<String>, DataDecodeError> {
+        let count = self.read_length(prefix)?;
+        let mut strings = Vec::new();
+        for _ in 0..count {
+            strings.push(self.read_string()?);
+        }
+        Ok(strings)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a count encoded as specified by
+    /// `prefix`, followed by that many values of any type implementing [CsharpReadable], as C#
+    /// code commonly writes an array.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the count is
+    /// negative.
+    pub fn read_array_of<R: CsharpReadable>(&mut self, prefix: LengthPrefix) -> Result<Vec<R>, DataDecodeError> {
+        let count = self.read_length(prefix)?;
+        let mut values = Vec::new();
+        for _ in 0..count {
+            values.push(R::read_from(self)?);
+        }
+        Ok(values)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a count encoded as specified by
+    /// `prefix`, followed by that many values read with `read_elem`, into a [`SmallVec`] which
+    /// stores up to `N` values without allocating. This avoids a heap allocation per sequence
+    /// when reading many short arrays.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the count is
+    /// negative.
+    /// Requires the `smallvec` feature.
+    ///
+    /// [`SmallVec`]: <https://docs.rs/smallvec/latest/smallvec/struct.SmallVec.html>
+    #[cfg_attr(docsrs, doc(cfg(feature = "smallvec")))]
+    #[cfg(feature = "smallvec")]
+    pub fn read_vec_small<R, const N: usize, F>(&mut self, prefix: LengthPrefix, mut read_elem: F) -> Result<smallvec::SmallVec<[R; N]>, DataDecodeError>
+    where F: FnMut(&mut Self) -> Result<R, DataDecodeError> {
+        let count = self.read_length(prefix)?;
+        let mut values = smallvec::SmallVec::new();
+        for _ in 0..count {
+            values.push(read_elem(self)?);
+        }
+        Ok(values)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a C# enum with an Int32 underlying
+    /// type, which is written as its discriminant, and converts it to `E`.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidEnumValue]) if the
+    /// conversion fails. The bytes are consumed either way.
+    pub fn read_enum<E: TryFrom<i32>>(&mut self) -> Result<E, DataDecodeError> {
+        E::try_from(self.read_i32()?).map_err(|_| InvalidDataError::InvalidEnumValue.into())
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a count encoded as specified by
+    /// `prefix`, followed by that many enums as read by [BinaryReader::read_enum], as C# code
+    /// writes an array of an enum with an Int32 underlying type.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidEnumValue]) if any
+    /// discriminant can't be converted, and
+    /// [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the count is
+    /// negative.
+    pub fn read_enum_array<E: TryFrom<i32>>(&mut self, prefix: LengthPrefix) -> Result<Vec<E>, DataDecodeError> {
+        let count = self.read_length(prefix)?;
+        let mut values = Vec::new();
+        for _ in 0..count {
+            values.push(self.read_enum()?);
+        }
+        Ok(values)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads values of any type implementing
+    /// [CsharpReadable] until the end of the data, for data which is a sequence of values with no
+    /// count.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NotEnoughBytes]) if the data ends
+    /// partway through a value, rather than returning the values read before it.
+    pub fn read_rest_as<R: CsharpReadable>(&mut self) -> Result<Vec<R>, DataDecodeError> {
+        let mut values = Vec::new();
+        while !self.is_at_eof()? {
+            values.push(R::read_from(self)?);
+        }
+        Ok(values)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads `count` 7-bit encoded ints, each the
+    /// difference from the previous value, with the first being the difference from zero, and
+    /// returns the running sum. This stores sequences of close values compactly.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::IntegerOverflow]) if a value
+    /// doesn't fit in an i32.
+    pub fn read_delta_i32_vec(&mut self, count: usize) -> Result<Vec<i32>, DataDecodeError> {
+        let mut values = Vec::new();
+        let mut value: i32 = 0;
+        for _ in 0..count {
+            value = value.checked_add(self.read_7_bit_encoded_int()?).ok_or(InvalidDataError::IntegerOverflow)?;
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads one message framed by an Int32 length
+    /// prefix, as is common in stream protocols, and returns its body.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the length is
+    /// negative.
+    pub fn read_frame(&mut self) -> Result<Vec<u8>, DataDecodeError> {
+        let length = self.read_length(LengthPrefix::Int32)?;
+        self.read_bytes(length)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a length encoded as specified by
+    /// `prefix`, followed by that many bytes, and returns a new BinaryReader over just those
+    /// bytes, so an embedded payload can be parsed without reading past its end.
+    /// The new reader has the same settings as this one, and starts at this reader's nesting
+    /// depth.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the length is
+    /// negative. If the data ends before the payload does, the length is still consumed.
+    pub fn read_nested(&mut self, prefix: LengthPrefix) -> Result<BinaryReader<Cursor<Vec<u8>>, B>, DataDecodeError> {
+        let length = self.read_length(prefix)?;
+        let payload = self.read_bytes(length)?;
+        Ok(self.with_input(Cursor::new(payload)))
+    }
+
+    /// Creates a new BinaryReader reading from `input` with the same settings as this one.
+    fn with_input<U: Read>(&self, input: U) -> BinaryReader<U, B> {
+        let mut reader = BinaryReader::with_byte_order(input);
+        reader.max_read_length = self.max_read_length;
+        reader.max_depth = self.max_depth;
+        reader.depth = self.depth;
+        reader.strict_floats = self.strict_floats;
+        reader.tolerant_7_bit_ints = self.tolerant_7_bit_ints;
+        reader.unsigned_string_lengths = self.unsigned_string_lengths;
+        reader.encoding = self.encoding;
+        reader.null_string_encoding = self.null_string_encoding;
+        reader
+    }
+
+    /// Doesn't correspond to any specific c# method. Returns an iterator which reads frames with
+    /// [BinaryReader::read_frame] until the end of the data. The end of the data between two
+    /// frames ends the iteration, while a truncated frame or any other error is yielded as an
+    /// error, after which the iterator ends.
+    pub fn frames(&mut self) -> Frames<'_, T, B> {
+        Frames::new(self)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads the header of a chunk in a RIFF or
+    /// IFF style format: a four byte id followed by the size of the payload as a UInt32, in the
+    /// byte order of this reader. RIFF is little-endian, while IFF is big-endian. The payload
+    /// isn't read.
+    pub fn read_chunk_header(&mut self) -> Result<([u8; 4], u32), DataDecodeError> {
+        let id = self.read_array()?;
+        let size = self.read_u32()?;
+        Ok((id, size))
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads any type implementing [CsharpReadable].
+    pub fn read<R: CsharpReadable>(&mut self) -> Result<R, DataDecodeError> {
+        R::read_from(self)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads two values in order and returns them
+    /// as a tuple, matching a C# ValueTuple written one element at a time.
+    pub fn read_tuple2<T1, T2>(&mut self) -> Result<(T1, T2), DataDecodeError>
+    where T1: CsharpReadable, T2: CsharpReadable {
+        self.read()
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads three values in order and returns them
+    /// as a tuple, matching a C# ValueTuple written one element at a time.
+    pub fn read_tuple3<T1, T2, T3>(&mut self) -> Result<(T1, T2, T3), DataDecodeError>
+    where T1: CsharpReadable, T2: CsharpReadable, T3: CsharpReadable {
+        self.read()
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads four values in order and returns them
+    /// as a tuple, matching a C# ValueTuple written one element at a time.
+    pub fn read_tuple4<T1, T2, T3, T4>(&mut self) -> Result<(T1, T2, T3, T4), DataDecodeError>
+    where T1: CsharpReadable, T2: CsharpReadable, T3: CsharpReadable, T4: CsharpReadable {
+        self.read()
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a value at `offset` bytes from the
+    /// start of the underlying stream, then seeks back to where the reader was. Useful for
+    /// fixed-layout records, or headers pointing at fields scattered through the data.
+    /// [BinaryReader::num_bytes_read] is also restored, so it is as if the value was never read.
+    /// The position is restored even if reading the value fails.
+    pub fn read_at<R: CsharpReadable>(&mut self, offset: u64) -> Result<R, DataDecodeError> where T: Seek {
+        let position = self.stream_position()?;
+        let num_bytes_read = self.num_bytes_read;
+        self.seek(SeekFrom::Start(offset))?;
+        let result = R::read_from(self);
+        self.seek(SeekFrom::Start(position))?;
+        self.num_bytes_read = num_bytes_read;
+        result
+    }
+
+    /// Doesn't correspond to any specific c# method. Equivalent to the Stream.Length property in
+    /// C#. Returns the total length of the underlying stream by seeking to its end and back,
+    /// without consuming or discarding anything. Comparing a declared length against this before
+    /// allocating rejects impossible lengths early.
+    pub fn stream_len(&mut self) -> std::io::Result<u64> where T: Seek {
+        let position = self.input.stream_position()?;
+        let len = self.input.seek(SeekFrom::End(0))?;
+        if len != position {
+            self.input.seek(SeekFrom::Start(position))?;
+        }
+        Ok(len)
+    }
     
 }
+
+macro_rules! read_with_byte_order {
+    ($type:ty, $read_le:ident, $read_be:ident) => {
+        #[doc = concat!("Doesn't correspond to any specific c# method. Reads a little-endian `", stringify!($type), "`, regardless of the byte order of this reader.")]
+        pub fn $read_le(&mut self) -> Result<$type, DataDecodeError> {
+            self.read_primitive_in::<$type, LittleEndian>()
+        }
+
+        #[doc = concat!("Doesn't correspond to any specific c# method. Reads a big-endian `", stringify!($type), "`, regardless of the byte order of this reader.")]
+        pub fn $read_be(&mut self) -> Result<$type, DataDecodeError> {
+            self.read_primitive_in::<$type, BigEndian>()
+        }
+    };
+}
+
+macro_rules! expect_value {
+    ($type:ty, $expect:ident, $read:ident) => {
+        #[doc = concat!("Doesn't correspond to any specific c# method. Reads a `", stringify!($type), "` with [BinaryReader::", stringify!($read), "] and checks that it equals `expected`, for fixed values such as a version number or a reserved zero.")]
+        /// Returns [DataDecodeError]::InvalidData([InvalidDataError::UnexpectedValue]) holding
+        /// both values if it doesn't. The bytes are consumed either way.
+        pub fn $expect(&mut self, expected: $type) -> Result<(), DataDecodeError> {
+            let found = self.$read()?;
+            if found != expected {
+                return Err(InvalidDataError::UnexpectedValue { expected: expected.into(), found: found.into() }.into());
+            }
+            Ok(())
+        }
+    };
+}
+
+/// Functions which read a value and check it against a constant.
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+    expect_value!(u8, expect_u8, read_byte);
+    expect_value!(i8, expect_i8, read_i8);
+    expect_value!(i16, expect_i16, read_i16);
+    expect_value!(i32, expect_i32, read_i32);
+    expect_value!(i64, expect_i64, read_i64);
+    expect_value!(u16, expect_u16, read_u16);
+    expect_value!(u32, expect_u32, read_u32);
+    expect_value!(u64, expect_u64, read_u64);
+    expect_value!(i32, expect_7_bit_encoded_int, read_7_bit_encoded_int);
+}
+
+macro_rules! read_or_default {
+    ($type:ty, $read_or:ident, $read:ident) => {
+        #[doc = concat!("Doesn't correspond to any specific c# method. Reads a `", stringify!($type), "` with [BinaryReader::", stringify!($read), "], or returns `default` if there are no more bytes, for optional trailing fields which older data doesn't have.")]
+        /// Data which ends partway through the value is still an error.
+        pub fn $read_or(&mut self, default: $type) -> Result<$type, DataDecodeError> {
+            if self.is_at_eof()? {
+                return Ok(default);
+            }
+            self.$read()
+        }
+    };
+}
+
+/// Functions which read a value, or return a default at the end of the data.
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+    read_or_default!(bool, read_boolean_or, read_boolean);
+    read_or_default!(u8, read_byte_or, read_byte);
+    read_or_default!(i8, read_i8_or, read_i8);
+    read_or_default!(i16, read_i16_or, read_i16);
+    read_or_default!(i32, read_i32_or, read_i32);
+    read_or_default!(i64, read_i64_or, read_i64);
+    read_or_default!(u16, read_u16_or, read_u16);
+    read_or_default!(u32, read_u32_or, read_u32);
+    read_or_default!(u64, read_u64_or, read_u64);
+    read_or_default!(f32, read_f32_or, read_f32);
+    read_or_default!(f64, read_f64_or, read_f64);
+    read_or_default!(i32, read_7_bit_encoded_int_or, read_7_bit_encoded_int);
+    read_or_default!(String, read_string_or, read_string);
+
+    /// Doesn't correspond to any specific c# method. Reads any type implementing
+    /// [CsharpReadable], or returns `default` if there are no more bytes. Data which ends partway
+    /// through the value is still an error.
+    pub fn read_or<R: CsharpReadable>(&mut self, default: R) -> Result<R, DataDecodeError> {
+        if self.is_at_eof()? {
+            return Ok(default);
+        }
+        R::read_from(self)
+    }
+}
+
+/// Functions which read in a specific byte order for a single call, for formats which mix byte
+/// orders, such as a big-endian header followed by a little-endian body.
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+    read_with_byte_order!(i16, read_i16_le, read_i16_be);
+    read_with_byte_order!(i32, read_i32_le, read_i32_be);
+    read_with_byte_order!(i64, read_i64_le, read_i64_be);
+    read_with_byte_order!(u16, read_u16_le, read_u16_be);
+    read_with_byte_order!(u32, read_u32_le, read_u32_be);
+    read_with_byte_order!(u64, read_u64_le, read_u64_be);
+    read_with_byte_order!(f32, read_f32_le, read_f32_be);
+    read_with_byte_order!(f64, read_f64_le, read_f64_be);
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "f16")))]
+    #[cfg(feature = "f16")]
+    read_with_byte_order!(f16, read_f16_le, read_f16_be);
+}
+
+/// Functions which take advantage of an underlying reader which already buffers its input.
+impl<T, B> BinaryReader<T, B>
+where T: BufRead, B: ByteOrder {
+
+    /// Reads the specified number of bytes like [BinaryReader::read_bytes], but copies them
+    /// directly out of the underlying BufRead's buffer instead of first reading them into this
+    /// reader's internal buffer. Any bytes previously buffered by a peek are returned first.
+    /// If there aren't enough bytes, no bytes are consumed.
+    pub fn read_bytes_buffered(&mut self, num_bytes: usize) -> Result<Vec<u8>, DataDecodeError> {
+        self.check_read_length(num_bytes)?;
+        let from_internal_buffer = num_bytes.min(self.buffered().len());
+        let mut output: Vec<u8> = self.take_buffered(from_internal_buffer);
+        while output.len() < num_bytes {
+            let available = match self.input.fill_buf() {
+                Ok(available) => available,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.restore_internal_buffer(&output, from_internal_buffer);
+                    return Err(e.into());
+                }
+            };
+            if available.is_empty() {
+                self.restore_internal_buffer(&output, from_internal_buffer);
+                return Err(InvalidDataError::NotEnoughBytes.into());
+            }
+            let count = available.len().min(num_bytes - output.len());
+            output.extend_from_slice(&available[0..count]);
+            self.input.consume(count);
+        }
+        self.num_bytes_read += num_bytes as u64;
+        Ok(output)
+    }
+
+    /// Moves back over the `from_internal_buffer` bytes taken from the internal buffer, and adds
+    /// the rest of `taken`, which came out of the underlying reader, to the internal buffer after
+    /// them so a failed read doesn't consume anything.
+    fn restore_internal_buffer(&mut self, taken: &[u8], from_internal_buffer: usize) {
+        self.pos -= from_internal_buffer;
+        self.buf.extend_from_slice(&taken[from_internal_buffer..]);
+    }
+}
+
+/// Seeking accounts for bytes this reader has buffered but not yet returned, and discards them.
+/// [BinaryReader::num_bytes_read] is not changed by seeking.
+impl<T, B> Seek for BinaryReader<T, B> where T: Read + Seek, B: ByteOrder {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Current(offset) => {
+                // the underlying reader is ahead of this one by the number of buffered bytes
+                let buffered: i64 = self.buffered().len().try_into().unwrap_or(i64::MAX);
+                SeekFrom::Current(offset.checked_sub(buffered).ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "seek offset overflowed")
+                })?)
+            },
+            other => other,
+        };
+        let position = self.input.seek(pos)?;
+        self.buf.clear();
+        self.pos = 0;
+        Ok(position)
+    }
+}
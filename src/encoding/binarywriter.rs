@@ -1,45 +1,159 @@
 
 use std::io;
 use std::io::Write;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::num::NonZeroU8;
+use super::csharpwritable::CsharpWritable;
+use super::framing::{LengthPrefix, NullStringEncoding, TagEncoding};
+use super::textencoding::Encoding;
+use super::byteorder::{to_bits, BigEndian, ByteOrder, LittleEndian, Primitive};
+use super::trace::trace_write;
+use std::marker::PhantomData;
 /// Analagous to the [`System.IO.BinaryWriter`] C# Class.
 ///
-/// Writes to any Write implementor.
+/// Writes to any Write implementor. Multi-byte numeric values are written in the byte order `B`,
+/// which defaults to [LittleEndian] to match C#.
+///
+/// The writer owns the Writer it is given, which is dropped along with it. To keep using the
+/// Writer afterward, as with the `leaveOpen` parameter of the C# constructor, pass a `&mut`
+/// reference to it instead. Unlike C#'s Dispose, dropping the writer doesn't flush the Writer, so
+/// call [BinaryWriter::flush] when the Writer buffers its output.
 ///
 /// [`System.IO.BinaryWriter`]:
 /// <https://learn.microsoft.com/en-us/dotnet/api/system.io.binarywriter>
-pub struct BinaryWriter<T: Write> {
+pub struct BinaryWriter<T: Write, B: ByteOrder = LittleEndian> {
     output: T,
-    num_bytes_written: u64
+    num_bytes_written: u64,
+    encoding: Encoding,
+    boolean_true_byte: NonZeroU8,
+    null_string_encoding: NullStringEncoding,
+    interned_strings: HashMap<String, i32>,
+    byte_order: PhantomData<B>
 }
 
+/// Writes one field of every row for [BinaryWriter::write_soa].
+type SoaWriteColumn<'a, T, B, R> = dyn FnMut(&mut BinaryWriter<T, B>, &R) -> io::Result<usize> + 'a;
+
 impl<T> BinaryWriter<T>
 where T: Write {
     
     ///Creates a new BinaryWriter which will write data to the provided Writer
     pub fn new(output: T) -> Self {
+        Self::with_byte_order(output)
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Creates a new BinaryWriter which will write data to the provided Writer using the byte
+    /// order `B`, e.g. `BinaryWriter::<_, BigEndian>::with_byte_order(output)`.
+    pub fn with_byte_order(output: T) -> Self {
         Self {
             output,
-            num_bytes_written: 0
+            num_bytes_written: 0,
+            encoding: Encoding::default(),
+            null_string_encoding: NullStringEncoding::default(),
+            boolean_true_byte: NonZeroU8::MIN,
+            interned_strings: HashMap::new(),
+            byte_order: PhantomData
         }
     }
 
+    /// Equivalent to the Flush method in C#. Flushes the underlying Writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+
+    /// Doesn't correspond to any specific c# method. Replaces the output Writer with
+    /// `new_output` and returns the old one, without flushing it. Interned strings and
+    /// [BinaryWriter::num_bytes_written] are cleared, and settings such as the encoding are kept.
+    pub fn reset(&mut self, new_output: T) -> T {
+        self.num_bytes_written = 0;
+        self.interned_strings.clear();
+        std::mem::replace(&mut self.output, new_output)
+    }
+
+    /// Doesn't correspond to any specific c# method. Calls `write` and returns its result along
+    /// with the number of bytes it wrote, measured with [BinaryWriter::num_bytes_written].
+    /// Useful for building offset tables without adding up the lengths of each write.
+    pub fn measured<F, R>(&mut self, write: F) -> io::Result<(R, u64)>
+    where F: FnOnce(&mut Self) -> io::Result<R> {
+        let start = self.num_bytes_written;
+        let result = write(self)?;
+        Ok((result, self.num_bytes_written - start))
+    }
+
+    /// Creates a new BinaryWriter writing to `output` with the same settings as this one.
+    fn with_output<U: Write>(&self, output: U) -> BinaryWriter<U, B> {
+        let mut writer = BinaryWriter::with_byte_order(output);
+        writer.encoding = self.encoding;
+        writer.null_string_encoding = self.null_string_encoding;
+        writer.boolean_true_byte = self.boolean_true_byte;
+        writer
+    }
+
     /// Returns the total number of bytes written to the underlying Writer
     pub fn num_bytes_written(&self) -> u64 {
         self.num_bytes_written
     }
 
+    /// Returns how null strings are marked by [BinaryWriter::write_nullable_string].
+    pub fn null_string_encoding(&self) -> NullStringEncoding {
+        self.null_string_encoding
+    }
+
+    /// Sets how null strings are marked by [BinaryWriter::write_nullable_string], to match the
+    /// reader of the data. Defaults to [NullStringEncoding::PresenceFlag].
+    pub fn set_null_string_encoding(&mut self, null_string_encoding: NullStringEncoding) {
+        self.null_string_encoding = null_string_encoding;
+    }
+
+    /// Returns the text encoding used when writing chars and strings.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Sets the text encoding used when writing chars and strings. Defaults to [Encoding::Utf8].
+    /// The byte order of this writer only affects [Encoding::Utf16], whose code units are in
+    /// that byte order; the other encodings, and the length prefix of strings, are the same in
+    /// either.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
+    /// Returns the byte written for `true` by [BinaryWriter::write_boolean].
+    pub fn boolean_true_byte(&self) -> NonZeroU8 {
+        self.boolean_true_byte
+    }
+
+    /// Sets the byte written for `true` by [BinaryWriter::write_boolean], for formats which
+    /// expect something other than C#'s `0x01`, such as `0xFF`. Defaults to `0x01`. It can't be
+    /// zero, so it is still read back as `true` by
+    /// [BinaryReader::read_boolean](crate::BinaryReader::read_boolean).
+    pub fn set_boolean_true_byte(&mut self, boolean_true_byte: NonZeroU8) {
+        self.boolean_true_byte = boolean_true_byte;
+    }
+
     /// Decrease the counter of number of bytes written by n
     pub fn remove_bytes_written(&mut self, n: u64) {
         self.num_bytes_written -= n;
     }
 
+    /// Reserves capacity for at least `additional` more bytes in the underlying Writer. Useful
+    /// to avoid repeated reallocation when writing many small values to an in-memory buffer.
+    pub fn reserve(&mut self, additional: usize) where T: ReserveCapacity {
+        self.output.reserve(additional);
+    }
+
     fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> where T: std::io::Seek {
         self.output.seek(pos)
     }
     
     /// Equivalent to the Write method in C# called with an argument of type Byte.
     pub fn write_byte(&mut self, data: u8) -> io::Result<usize> {
-        self.write_bytes(&[data])
+        trace_write!(self, "u8", data, self.write_bytes(&[data]))
     }
 
     /// Equivalent to the Write method in C# called with an argument of type Byte[].
@@ -48,6 +162,16 @@ where T: Write {
         self.output.write(data)
     }
 
+    /// Doesn't correspond to any specific c# method. Writes the length of `data` as an Int32
+    /// followed by its bytes, like `writer.Write(bytes.Length)` followed by `writer.Write(bytes)`
+    /// in C#. Read back by [BinaryReader::read_byte_array](crate::BinaryReader::read_byte_array).
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] without writing anything if `data`
+    /// is longer than `i32::MAX` bytes.
+    pub fn write_byte_array(&mut self, data: &[u8]) -> io::Result<usize> {
+        let written = self.write_length(LengthPrefix::Int32, data.len())?;
+        Ok(written + self.write_bytes(data)?)
+    }
+
     // Implementation translated from the c# dotnet runtime's implementation of BinaryWriter
     // MIT Licensed by the .NET foundation, can be found at https://github.com/dotnet/runtime
     /// Equivalent to the Write7BitEncodedInt method in C#.
@@ -60,7 +184,14 @@ where T: Write {
             out_bytes.push(low_bits_and_flag);
         }
         out_bytes.push(value.to_le_bytes()[0]);
-        self.write_bytes(&out_bytes)
+        trace_write!(self, "7-bit encoded int", data, self.write_bytes(&out_bytes))
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a UInt32 as a 7-bit encoded int, which
+    /// produces the same bytes as C# writing the same 32 bits as an Int32. Read back by
+    /// [BinaryReader::read_7_bit_encoded_uint](crate::BinaryReader::read_7_bit_encoded_uint).
+    pub fn write_7_bit_encoded_uint(&mut self, data: u32) -> io::Result<usize> {
+        self.write_7_bit_encoded_int(data as i32)
     }
 
     // Implementation translated from the c# dotnet runtime's implementation of BinaryWriter
@@ -75,94 +206,635 @@ where T: Write {
             out_bytes.push(low_bits_and_flag);
         }
         out_bytes.push(value.to_le_bytes()[0]);
-        self.write_bytes(&out_bytes)
+        trace_write!(self, "7-bit encoded int", data, self.write_bytes(&out_bytes))
+    }
+    
+    /// Writes a fixed-size numeric value in this writer's byte order.
+    fn write_primitive<N: Primitive>(&mut self, data: N) -> io::Result<usize> {
+        self.write_primitive_in::<N, B>(data)
+    }
+
+    /// Writes a fixed-size numeric value in the byte order `O`, regardless of this writer's.
+    fn write_primitive_in<N: Primitive, O: ByteOrder>(&mut self, data: N) -> io::Result<usize> {
+        trace_write!(self, std::any::type_name::<N>(), data, self.write_bytes(O::to_bytes(data).as_ref()))
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes any numeric [Primitive] in
+    /// little-endian byte order, regardless of the byte order of this writer, so generic code can
+    /// write a value without knowing its concrete type.
+    pub fn write_le<N: Primitive>(&mut self, data: N) -> io::Result<usize> {
+        self.write_primitive_in::<N, LittleEndian>(data)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes any numeric [Primitive] in big-endian
+    /// byte order, regardless of the byte order of this writer.
+    pub fn write_be<N: Primitive>(&mut self, data: N) -> io::Result<usize> {
+        self.write_primitive_in::<N, BigEndian>(data)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes every value in `data` in
+    /// little-endian byte order, regardless of the byte order of this writer, with a single call
+    /// to the underlying Writer. Returns the number of bytes written.
+    ///
+    /// With the `bytemuck` feature on a little-endian target, the values are written straight
+    /// from memory without being copied first.
+    pub fn write_slice_le<N: Primitive>(&mut self, data: &[N]) -> io::Result<usize> {
+        match N::slice_as_le_bytes(data) {
+            Some(bytes) => self.write_all(bytes),
+            None => self.write_slice_in::<N, LittleEndian>(data),
+        }
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes every value in `data` in big-endian
+    /// byte order, regardless of the byte order of this writer, with a single call to the
+    /// underlying Writer. Returns the number of bytes written.
+    pub fn write_slice_be<N: Primitive>(&mut self, data: &[N]) -> io::Result<usize> {
+        self.write_slice_in::<N, BigEndian>(data)
+    }
+
+    /// Converts every value in `data` to bytes in the byte order `O` and writes them all at once.
+    fn write_slice_in<N: Primitive, O: ByteOrder>(&mut self, data: &[N]) -> io::Result<usize> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(data.len() * size_of::<N::Bytes>());
+        for value in data {
+            bytes.extend_from_slice(O::to_bytes(*value).as_ref());
+        }
+        self.write_all(&bytes)
+    }
+
+    /// Writes all of `data`, retrying until the underlying Writer has accepted it.
+    fn write_all(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.output.write_all(data)?;
+        self.num_bytes_written += data.len() as u64;
+        Ok(data.len())
+    }
+    
+    /// Doesn't correspond to any specific c# method. Writes a zigzag encoded varint, as used by
+    /// Protocol Buffers' `sint32`.
+    ///
+    /// Zigzag encoding maps signed values to unsigned ones so that values close to zero, including
+    /// negative ones, are small: 0, -1, 1, -2 become 0, 1, 2, 3. The result is then 7-bit encoded.
+    /// This differs from [BinaryWriter::write_7_bit_encoded_int], which writes the two's
+    /// complement bit pattern directly, so any negative value takes the full 5 bytes.
+    pub fn write_zigzag_varint32(&mut self, data: i32) -> io::Result<usize> {
+        self.write_7_bit_encoded_int((data << 1) ^ (data >> 31))
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a zigzag encoded varint, as used by
+    /// Protocol Buffers' `sint64`. See [BinaryWriter::write_zigzag_varint32] for how this differs
+    /// from [BinaryWriter::write_7_bit_encoded_int64].
+    pub fn write_zigzag_varint64(&mut self, data: i64) -> io::Result<usize> {
+        self.write_7_bit_encoded_int64((data << 1) ^ (data >> 63))
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a signed LEB128 value, as used by
+    /// DWARF and WebAssembly.
+    ///
+    /// Signed LEB128 sign-extends the value from bit 6 of its last byte, so small negative values
+    /// are as short as small positive ones: -1 is the single byte `0x7F`. This differs from
+    /// [BinaryWriter::write_7_bit_encoded_int], which writes the two's complement bit pattern
+    /// directly, so any negative value takes the full 5 bytes.
+    pub fn write_leb128_i32(&mut self, data: i32) -> io::Result<usize> {
+        self.write_leb128_i64(data.into())
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a signed LEB128 value. See
+    /// [BinaryWriter::write_leb128_i32] for how this differs from
+    /// [BinaryWriter::write_7_bit_encoded_int64].
+    pub fn write_leb128_i64(&mut self, mut data: i64) -> io::Result<usize> {
+        let mut bytes: Vec<u8> = Vec::new();
+        loop {
+            let byte = (data & 0b01111111) as u8;
+            data >>= 7;
+            let sign_bit_set = byte & 0b01000000 != 0;
+            if (data == 0 && !sign_bit_set) || (data == -1 && sign_bit_set) {
+                bytes.push(byte);
+                break;
+            }
+            bytes.push(byte | 0b10000000);
+        }
+        self.write_bytes(&bytes)
     }
     
     /// Equivalent to the Write method in C# called with an argument of type Boolean.
+    /// `true` is written as [BinaryWriter::boolean_true_byte], which is `0x01` by default.
     pub fn write_boolean(&mut self, data: bool) -> io::Result<usize> {
         // explicitely use C#'s binary representation of bool
         // without making assumptions about how rust stores bool values 
         // in memory
-        if data {
-            self.write_byte(1) 
-        } else {
-            self.write_byte(0)
+        let byte = if data { self.boolean_true_byte.get() } else { 0 };
+        trace_write!(self, "bool", data, self.write_bytes(&[byte]))
+    }
+    
+    /// Doesn't correspond to any specific c# method. Packs eight flags into a single byte. The bit
+    /// order is least significant bit first, so `flags[0]` becomes `0x01` and `flags[7]` becomes
+    /// `0x80`, matching [BinaryReader::read_flags](crate::BinaryReader::read_flags).
+    pub fn write_flags(&mut self, flags: [bool; 8]) -> io::Result<usize> {
+        let byte = flags.iter()
+            .enumerate()
+            .fold(0_u8, |byte, (index, flag)| byte | ((*flag as u8) << index));
+        self.write_byte(byte)
+    }
+    
+    /// Doesn't correspond to any specific c# method. Writes the one byte marker many .NET
+    /// serializers write before a reference type: 1 if a value follows, or 0 for null.
+    pub fn write_is_present(&mut self, present: bool) -> io::Result<usize> {
+        self.write_byte(present as u8)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a marker as written by
+    /// [BinaryWriter::write_is_present], followed by the value written with `write_value` if
+    /// there is one. Returns the total number of bytes written.
+    pub fn write_optional<V, F, R>(&mut self, value: Option<V>, write_value: F) -> io::Result<usize>
+    where F: FnOnce(&mut Self, V) -> io::Result<R> {
+        let start = self.num_bytes_written;
+        self.write_is_present(value.is_some())?;
+        if let Some(value) = value {
+            write_value(self, value)?;
         }
+        Ok((self.num_bytes_written - start) as usize)
+    }
+    
+    /// Doesn't correspond to any specific c# method. Writes a C# `[Flags]` enum value as its
+    /// underlying integer type `N`, like
+    /// [BinaryReader::read_flags_enum](crate::BinaryReader::read_flags_enum) reads it.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] without writing anything if any bits
+    /// outside `valid_mask` are set.
+    pub fn write_flags_enum<N: Primitive>(&mut self, data: N, valid_mask: u64) -> io::Result<usize> {
+        if to_bits(data) & !valid_mask != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "flags value has undefined bits set"));
+        }
+        self.write_primitive(data)
     }
     
     /// Equivalent to the Write method in C# called with an argument of type Single
     pub fn write_f32(&mut self, data: f32) -> io::Result<usize> {
-        self.write_bytes(&data.to_le_bytes())
+        self.write_primitive(data)
     }
 
     /// Equivalent to the Write method in C# called with an argument of type Double
     pub fn write_f64(&mut self, data: f64) -> io::Result<usize> {
-        self.write_bytes(&data.to_le_bytes())
+        self.write_primitive(data)
     }
 
     /// Equivalent to the Write method in C# called with an argument of type Half
     #[cfg_attr(docsrs, doc(cfg(feature = "f16")))]
     #[cfg(feature = "f16")]
     pub fn write_f16(&mut self, data: f16) -> io::Result<usize> {
-        self.write_bytes(&data.to_le_bytes())
+        self.write_primitive(data)
     }
 
     /// Equivalent to the Write method in C# called with an argument of type String
+    /// Uses the writer's [Encoding], and like C# the length prefix is the number of bytes rather
+    /// than the number of chars.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] if the encoded string is longer than
+    /// `i32::MAX` bytes.
     pub fn write_string(&mut self, data: &str) -> io::Result<usize> {
-        // first, write the number of bytes the string will take up in utf-8
-        if let Err(e) = self.write_7_bit_encoded_int(data.len().try_into().unwrap()) {
-            return Err(e)
+        trace_write!(self, "string", data, {
+            let encoded: Cow<[u8]> = match self.encoding {
+                // rust str is gauranteed to be valid utf-8 so no further processing is needed.
+                Encoding::Utf8 => Cow::Borrowed(data.as_bytes()),
+                encoding => Cow::Owned(encoding.encode::<B>(data)),
+            };
+            // first, write the number of bytes the string will take up, then the encoded data
+            let prefix_len = self.write_length(LengthPrefix::SevenBitEncodedInt, encoded.len())?;
+            Ok(prefix_len + self.write_bytes(&encoded)?)
+        })
+    }
+    
+    /// Doesn't correspond to any specific c# method. Writes a string which may be null, marked as
+    /// specified by [BinaryWriter::null_string_encoding]. The string itself is written like
+    /// [BinaryWriter::write_string]. Returns the total number of bytes written.
+    pub fn write_nullable_string(&mut self, data: Option<&str>) -> io::Result<usize> {
+        match (self.null_string_encoding, data) {
+            (NullStringEncoding::PresenceFlag, data) => self.write_optional(data, |writer, data| writer.write_string(data)),
+            (NullStringEncoding::SentinelLength, Some(data)) => self.write_string(data),
+            (NullStringEncoding::SentinelLength, None) => self.write_7_bit_encoded_int(-1),
         }
-        // then, write the utf-8 data. rust str is gauranteed to be valid utf-8 so no further
-        // processing is needed.
-        self.write_bytes(data.as_bytes())
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes the number of bytes in `data` as a
+    /// UInt32, followed by its utf-8 bytes, as some serializers write strings instead of using a
+    /// 7-bit encoded length. Returns the total number of bytes written.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] if `data` is longer than
+    /// `u32::MAX` bytes.
+    pub fn write_string_u32_prefixed(&mut self, data: &str) -> io::Result<usize> {
+        let prefix_len = self.write_length(LengthPrefix::UInt32, data.len())?;
+        Ok(prefix_len + self.write_bytes(data.as_bytes())?)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes the number of characters in `data`
+    /// encoded as specified by `prefix`, followed by its utf-8 bytes, for formats which count
+    /// characters rather than bytes. Returns the total number of bytes written.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] if the number of characters can't be
+    /// represented by `prefix`.
+    pub fn write_string_by_char_count(&mut self, prefix: LengthPrefix, data: &str) -> io::Result<usize> {
+        let prefix_len = self.write_length(prefix, data.chars().count())?;
+        Ok(prefix_len + self.write_bytes(data.as_bytes())?)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a string using this writer's string
+    /// table, so repeated strings take up only a few bytes.
+    /// The first time a string is written, a 7-bit encoded 0 is written followed by the string as
+    /// in [BinaryWriter::write_string], and the string is added to the table. After that, only
+    /// one more than its index in the table is written, as a 7-bit encoded int.
+    /// Read it back with
+    /// [BinaryReader::read_interned_string](crate::BinaryReader::read_interned_string).
+    pub fn write_interned_string(&mut self, data: &str) -> io::Result<usize> {
+        if let Some(&index) = self.interned_strings.get(data) {
+            return self.write_7_bit_encoded_int(index + 1);
+        }
+        let index = self.interned_strings.len().try_into()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "too many interned strings"))?;
+        let written = self.write_7_bit_encoded_int(0)? + self.write_string(data)?;
+        self.interned_strings.insert(data.to_string(), index);
+        Ok(written)
+    }
+
+    /// Forgets every string written by [BinaryWriter::write_interned_string], so each is written
+    /// in full the next time it occurs.
+    pub fn clear_interned_strings(&mut self) {
+        self.interned_strings.clear();
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes the utf-8 bytes of `data` followed by
+    /// a null terminator, as used by C and by strings marshalled with P/Invoke.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] without writing anything if `data`
+    /// contains a null character, since it couldn't be read back.
+    pub fn write_cstring(&mut self, data: &str) -> io::Result<usize> {
+        if data.contains('\0') {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "string contains an interior null character"));
+        }
+        let written = self.write_bytes(data.as_bytes())?;
+        Ok(written + self.write_byte(0)?)
     }
     
     /// Equivalent to the Write method in C# called with an argument of type SByte
     pub fn write_i8(&mut self, data: i8) -> io::Result<usize> {
-        self.write_bytes(&data.to_le_bytes())
+        trace_write!(self, "i8", data, self.write_bytes(&data.to_le_bytes()))
     }
 
     /// Equivalent to the Write method in C# called with an argument of type Int16
     pub fn write_i16(&mut self, data: i16) -> io::Result<usize> {
-        self.write_bytes(&data.to_le_bytes())
+        self.write_primitive(data)
     }
 
     /// Equivalent to the Write method in C# called with an argument of type Int32
     pub fn write_i32(&mut self, data: i32) -> io::Result<usize> {
-        self.write_bytes(&data.to_le_bytes())
+        self.write_primitive(data)
     }
 
     /// Equivalent to the Write method in C# called with an argument of type Int64
     pub fn write_i64(&mut self, data: i64) -> io::Result<usize> {
-        self.write_bytes(&data.to_le_bytes())
+        self.write_primitive(data)
     }
 
     /// Equivalent to the Write method in C# called with an argument of type UInt16
     pub fn write_u16(&mut self, data: u16) -> io::Result<usize> {
-        self.write_bytes(&data.to_le_bytes())
+        self.write_primitive(data)
     }
 
     /// Equivalent to the Write method in C# called with an argument of type UInt32
     pub fn write_u32(&mut self, data: u32) -> io::Result<usize> {
-        self.write_bytes(&data.to_le_bytes())
+        self.write_primitive(data)
     }
 
     /// Equivalent to the Write method in C# called with an argument of type UInt64
     pub fn write_u64(&mut self, data: u64) -> io::Result<usize> {
-        self.write_bytes(&data.to_le_bytes())
+        self.write_primitive(data)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a usize as a UInt64, so the data has
+    /// the same layout regardless of the platform's pointer width.
+    pub fn write_usize_as_u64(&mut self, data: usize) -> io::Result<usize> {
+        self.write_u64(data as u64)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes an isize as an Int64, so the data has
+    /// the same layout regardless of the platform's pointer width.
+    pub fn write_isize_as_i64(&mut self, data: isize) -> io::Result<usize> {
+        self.write_i64(data as i64)
     }
 
-    /// Equivalent to the Write method in C# called with an argument of type Char
+    /// Equivalent to the Write method in C# called with an argument of type Char.
+    /// Uses the writer's [Encoding]; under the utf-16 encodings chars outside the basic multilingual plane are written as a surrogate pair.
     pub fn write_char(&mut self, data: char) -> io::Result<usize> {
-        let mut buf: [u8; 4] = [0; 4];
-        self.write_bytes(data.encode_utf8(buf.as_mut_slice()).as_bytes())
+        trace_write!(self, "char", data, match self.encoding {
+            Encoding::Utf8 => {
+                let mut buf: [u8; 4] = [0; 4];
+                self.write_bytes(data.encode_utf8(buf.as_mut_slice()).as_bytes())
+            },
+            Encoding::Utf16 => self.write_utf16_char::<B>(data),
+            Encoding::Utf16BigEndian => self.write_utf16_char::<BigEndian>(data),
+            Encoding::Utf16LittleEndian => self.write_utf16_char::<LittleEndian>(data),
+            Encoding::Latin1 => self.write_bytes(&[u8::try_from(data).unwrap_or(b'?')]),
+        })
+    }
+
+    /// Writes a char as one or two utf-16 code units in the byte order `O`.
+    fn write_utf16_char<O: ByteOrder>(&mut self, data: char) -> io::Result<usize> {
+        let mut units: [u16; 2] = [0; 2];
+        let mut bytes: Vec<u8> = Vec::with_capacity(4);
+        for unit in data.encode_utf16(units.as_mut_slice()) {
+            bytes.extend_from_slice(&O::to_bytes(*unit));
+        }
+        self.write_bytes(&bytes)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes `tag` encoded as specified by
+    /// `encoding`, then calls `write_payload` to write the payload. Returns the total number of
+    /// bytes written for the tag and payload.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] if `tag` can't be represented
+    /// by `encoding`.
+    pub fn write_tagged<F, R>(&mut self, encoding: TagEncoding, tag: i32, write_payload: F) -> io::Result<usize>
+    where F: FnOnce(&mut Self) -> io::Result<R> {
+        let start = self.num_bytes_written;
+        match encoding {
+            TagEncoding::Byte => {
+                let tag: u8 = tag.try_into().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "tag does not fit in a byte")
+                })?;
+                self.write_byte(tag)?
+            },
+            TagEncoding::Int32 => self.write_i32(tag)?,
+            TagEncoding::SevenBitEncodedInt => self.write_7_bit_encoded_int(tag)?,
+        };
+        write_payload(self)?;
+        Ok((self.num_bytes_written - start) as usize)
+    }
+
+    /// Writes a length or element count encoded as specified by `prefix`.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] if `length` can't be represented by
+    /// `prefix`.
+    pub(crate) fn write_length(&mut self, prefix: LengthPrefix, length: usize) -> io::Result<usize> {
+        let too_long = || io::Error::new(io::ErrorKind::InvalidInput, "length does not fit in the length prefix");
+        match prefix {
+            LengthPrefix::SevenBitEncodedInt => self.write_7_bit_encoded_int(length.try_into().map_err(|_| too_long())?),
+            LengthPrefix::Int32 => self.write_i32(length.try_into().map_err(|_| too_long())?),
+            LengthPrefix::UInt16 => self.write_u16(length.try_into().map_err(|_| too_long())?),
+            LengthPrefix::UInt32 => self.write_u32(length.try_into().map_err(|_| too_long())?),
+        }
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes the number of pairs encoded as
+    /// specified by `prefix`, followed by each key and value, as C# code commonly writes a
+    /// `Dictionary<K, V>`. Returns the total number of bytes written.
+    pub fn write_map<I, K, V, FK, FV, RK, RV>(&mut self, prefix: LengthPrefix, pairs: I, mut write_key: FK, mut write_value: FV) -> io::Result<usize>
+    where I: IntoIterator<Item = (K, V)>, I::IntoIter: ExactSizeIterator,
+          FK: FnMut(&mut Self, K) -> io::Result<RK>, FV: FnMut(&mut Self, V) -> io::Result<RV> {
+        let start = self.num_bytes_written;
+        let pairs = pairs.into_iter();
+        self.write_length(prefix, pairs.len())?;
+        for (key, value) in pairs {
+            write_key(self, key)?;
+            write_value(self, value)?;
+        }
+        Ok((self.num_bytes_written - start) as usize)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a sparse array as read by
+    /// [BinaryReader::read_sparse](crate::BinaryReader::read_sparse): the number of elements
+    /// which are `Some` as an Int32, followed by the index of each as an Int32 and its value
+    /// written with `write_value`. Returns the total number of bytes written.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] without writing anything if
+    /// `values` is longer than `i32::MAX` elements.
+    pub fn write_sparse<V, F, R>(&mut self, values: &[Option<V>], mut write_value: F) -> io::Result<usize>
+    where F: FnMut(&mut Self, &V) -> io::Result<R> {
+        if i32::try_from(values.len()).is_err() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "sparse array has too many elements"));
+        }
+        let start = self.num_bytes_written;
+        self.write_length(LengthPrefix::Int32, values.iter().flatten().count())?;
+        for (index, value) in values.iter().enumerate() {
+            if let Some(value) = value {
+                self.write_i32(index as i32)?;
+                write_value(self, value)?;
+            }
+        }
+        Ok((self.num_bytes_written - start) as usize)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes each key and value with no count, to
+    /// be read back by [BinaryReader::read_pairs_until_eof](crate::BinaryReader::read_pairs_until_eof).
+    /// Returns the total number of bytes written.
+    pub fn write_pairs<I, K, V, FK, FV, RK, RV>(&mut self, pairs: I, mut write_key: FK, mut write_value: FV) -> io::Result<usize>
+    where I: IntoIterator<Item = (K, V)>,
+          FK: FnMut(&mut Self, K) -> io::Result<RK>, FV: FnMut(&mut Self, V) -> io::Result<RV> {
+        let start = self.num_bytes_written;
+        for (key, value) in pairs {
+            write_key(self, key)?;
+            write_value(self, value)?;
+        }
+        Ok((self.num_bytes_written - start) as usize)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes `rows` column by column: each entry
+    /// of `columns` writes one field of a row, and is called for every row before the next
+    /// column is started. The row count isn't written. This is read back by
+    /// [BinaryReader::read_soa](crate::BinaryReader::read_soa). Returns the total number of
+    /// bytes written.
+    pub fn write_soa<R>(&mut self, rows: &[R], columns: &mut [&mut SoaWriteColumn<'_, T, B, R>]) -> io::Result<usize> {
+        let start = self.num_bytes_written;
+        for column in columns.iter_mut() {
+            for row in rows {
+                column(self, row)?;
+            }
+        }
+        Ok((self.num_bytes_written - start) as usize)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes the number of strings encoded as
+    /// specified by `prefix`, followed by each string as written by [BinaryWriter::write_string],
+    /// as C# code commonly writes a `string[]`. Returns the total number of bytes written.
+    pub fn write_string_array<S: AsRef<str>>(&mut self, prefix: LengthPrefix, strings: &[S]) -> io::Result<usize> {
+        let mut written = self.write_length(prefix, strings.len())?;
+        for string in strings {
+            written += self.write_string(string.as_ref())?;
+        }
+        Ok(written)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes any type implementing
+    /// [CsharpWritable].
+    pub fn write<W: CsharpWritable + ?Sized>(&mut self, value: &W) -> io::Result<usize> {
+        value.write_to(self)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes the number of values encoded as
+    /// specified by `prefix`, followed by each value, as C# code commonly writes an array.
+    /// Returns the total number of bytes written.
+    pub fn write_array_of<W: CsharpWritable>(&mut self, prefix: LengthPrefix, values: &[W]) -> io::Result<usize> {
+        let mut written = self.write_length(prefix, values.len())?;
+        for value in values {
+            written += value.write_to(self)?;
+        }
+        Ok(written)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes an enum as its Int32 discriminant,
+    /// like C# writes an enum with an Int32 underlying type.
+    pub fn write_enum<E: Into<i32>>(&mut self, data: E) -> io::Result<usize> {
+        self.write_i32(data.into())
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes the number of enums encoded as
+    /// specified by `prefix`, followed by each as written by [BinaryWriter::write_enum].
+    /// Returns the total number of bytes written.
+    pub fn write_enum_array<E: Copy + Into<i32>>(&mut self, prefix: LengthPrefix, values: &[E]) -> io::Result<usize> {
+        let mut written = self.write_length(prefix, values.len())?;
+        for &value in values {
+            written += self.write_enum(value)?;
+        }
+        Ok(written)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes each value in `data` as the
+    /// difference from the previous value, with the first being the difference from zero, as a
+    /// 7-bit encoded int. The count isn't written. See
+    /// [BinaryReader::read_delta_i32_vec](crate::BinaryReader::read_delta_i32_vec).
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] without writing anything if the
+    /// difference between two consecutive values doesn't fit in an i32.
+    pub fn write_delta_i32_slice(&mut self, data: &[i32]) -> io::Result<usize> {
+        let mut deltas: Vec<i32> = Vec::with_capacity(data.len());
+        let mut previous: i32 = 0;
+        for &value in data {
+            let delta = value.checked_sub(previous).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "difference between values doesn't fit in an i32")
+            })?;
+            deltas.push(delta);
+            previous = value;
+        }
+        let mut written = 0;
+        for delta in deltas {
+            written += self.write_7_bit_encoded_int(delta)?;
+        }
+        Ok(written)
     }
 
+    /// Doesn't correspond to any specific c# method. Writes the header of a chunk in a RIFF or
+    /// IFF style format: a four byte id followed by the size of the payload as a UInt32, in the
+    /// byte order of this writer. See [BinaryReader::read_chunk_header](crate::BinaryReader::read_chunk_header).
+    pub fn write_chunk_header(&mut self, id: [u8; 4], size: u32) -> io::Result<usize> {
+        Ok(self.write_bytes(&id)? + self.write_u32(size)?)
+    }
+
+    /// Doesn't correspond to any specific c# method. Calls `write_body` to write the body, then
+    /// writes the number of bytes it wrote encoded as specified by `suffix`, for formats which
+    /// put the length after the data. Returns the total number of bytes written for the body and
+    /// the length.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] if the length of the body can't be
+    /// represented by `suffix`. The body has already been written when this happens.
+    pub fn with_length_suffix<F, R>(&mut self, suffix: LengthPrefix, write_body: F) -> io::Result<usize>
+    where F: FnOnce(&mut Self) -> io::Result<R> {
+        let start = self.num_bytes_written;
+        write_body(self)?;
+        let body_len = (self.num_bytes_written - start) as usize;
+        Ok(body_len + self.write_length(suffix, body_len)?)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a placeholder Int32, calls
+    /// `write_body` to write the body, then seeks back and replaces the placeholder with the
+    /// number of bytes the body took up. The writer is left positioned after the body. Returns the
+    /// total number of bytes written for the length and the body.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] if the body is longer than
+    /// `i32::MAX` bytes, in which case the placeholder is left as 0.
+    pub fn with_length_prefix<F, R>(&mut self, write_body: F) -> io::Result<usize>
+    where T: std::io::Seek, F: FnOnce(&mut Self) -> io::Result<R> {
+        let prefix_position = self.output.stream_position()?;
+        let start = self.num_bytes_written;
+        let prefix_len = self.write_i32(0)?;
+        write_body(self)?;
+        let body_len = (self.num_bytes_written - start) as usize - prefix_len;
+        let body_len: i32 = body_len.try_into().map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, "length does not fit in the length prefix")
+        })?;
+        let end_position = self.output.stream_position()?;
+        // the placeholder has already been counted, so patch it directly in the output
+        self.seek(io::SeekFrom::Start(prefix_position))?;
+        self.output.write_all(B::to_bytes(body_len).as_ref())?;
+        self.seek(io::SeekFrom::Start(end_position))?;
+        Ok((self.num_bytes_written - start) as usize)
+    }
+
+    /// Doesn't correspond to any specific c# method. Calls `write_section` with a writer which
+    /// writes to memory, and returns the number of bytes it wrote along with the bytes, without
+    /// writing anything to this writer. The caller can then write a header which depends on the
+    /// length, followed by the bytes, even when the underlying Writer can't seek. See
+    /// [BinaryWriter::with_length_prefix] for Writers which can.
+    ///
+    /// The section is written with the same settings as this writer, and shares its table of
+    /// interned strings, so the bytes should be written to this writer before any more interned
+    /// strings are.
+    pub fn buffered_section<F, R>(&mut self, write_section: F) -> io::Result<(usize, Vec<u8>)>
+    where F: FnOnce(&mut BinaryWriter<Vec<u8>, B>) -> io::Result<R> {
+        let mut section = self.with_output(Vec::new());
+        section.interned_strings = std::mem::take(&mut self.interned_strings);
+        let result = write_section(&mut section);
+        self.interned_strings = std::mem::take(&mut section.interned_strings);
+        result?;
+        let length = section.num_bytes_written as usize;
+        Ok((length, section.output))
+    }
+}
+
+macro_rules! write_with_byte_order {
+    ($type:ty, $write_le:ident, $write_be:ident) => {
+        #[doc = concat!("Doesn't correspond to any specific c# method. Writes a little-endian `", stringify!($type), "`, regardless of the byte order of this writer.")]
+        pub fn $write_le(&mut self, data: $type) -> io::Result<usize> {
+            self.write_primitive_in::<$type, LittleEndian>(data)
+        }
+
+        #[doc = concat!("Doesn't correspond to any specific c# method. Writes a big-endian `", stringify!($type), "`, regardless of the byte order of this writer.")]
+        pub fn $write_be(&mut self, data: $type) -> io::Result<usize> {
+            self.write_primitive_in::<$type, BigEndian>(data)
+        }
+    };
+}
+
+/// Functions which write in a specific byte order for a single call, for formats which mix byte
+/// orders, such as a big-endian header followed by a little-endian body.
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+    write_with_byte_order!(i16, write_i16_le, write_i16_be);
+    write_with_byte_order!(i32, write_i32_le, write_i32_be);
+    write_with_byte_order!(i64, write_i64_le, write_i64_be);
+    write_with_byte_order!(u16, write_u16_le, write_u16_be);
+    write_with_byte_order!(u32, write_u32_le, write_u32_be);
+    write_with_byte_order!(u64, write_u64_le, write_u64_be);
+    write_with_byte_order!(f32, write_f32_le, write_f32_be);
+    write_with_byte_order!(f64, write_f64_le, write_f64_be);
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "f16")))]
+    #[cfg(feature = "f16")]
+    write_with_byte_order!(f16, write_f16_le, write_f16_be);
 }
 
-impl<T> std::io::Seek for BinaryWriter<T> where T: std::io::Seek + std::io::Write {
+impl<T, B> std::io::Seek for BinaryWriter<T, B> where T: std::io::Seek + std::io::Write, B: ByteOrder {
     fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
         self.seek(pos)
     }
 }
+
+/// Implemented by in-memory Writers which can reserve capacity ahead of time.
+/// See [BinaryWriter::reserve].
+pub trait ReserveCapacity {
+    /// Reserves capacity for at least `additional` more bytes beyond the current length.
+    fn reserve(&mut self, additional: usize);
+}
+
+impl ReserveCapacity for Vec<u8> {
+    fn reserve(&mut self, additional: usize) {
+        Vec::reserve(self, additional);
+    }
+}
+
+impl<V> ReserveCapacity for io::Cursor<V> where V: ReserveCapacity {
+    fn reserve(&mut self, additional: usize) {
+        self.get_mut().reserve(additional);
+    }
+}
+
+impl<R> ReserveCapacity for &mut R where R: ReserveCapacity + ?Sized {
+    fn reserve(&mut self, additional: usize) {
+        (**self).reserve(additional);
+    }
+}
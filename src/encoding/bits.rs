@@ -0,0 +1,200 @@
+use std::io::{self, Read, Write};
+use super::binaryreader::{BinaryReader, DataDecodeError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+/// Selects the order bits are packed into each byte by a [BitReader] or [BitWriter].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum BitOrder {
+    /// The first bit is the most significant bit of each byte, and the first bit of a value is
+    /// its most significant bit. This is how most bit-packed file formats are laid out.
+    #[default]
+    MsbFirst,
+    /// The first bit is the least significant bit of each byte, and the first bit of a value is
+    /// its least significant bit, as used by deflate and C#'s `BitArray`.
+    LsbFirst,
+}
+
+/// Reads values which are packed at bit granularity rather than byte granularity, from a
+/// [BinaryReader].
+///
+/// Bytes are read from the [BinaryReader] one at a time as they are needed, and the bits of a
+/// partially read byte are kept until the next call. Any bits left in the current byte when the
+/// BitReader is dropped are discarded, so the [BinaryReader] continues at the next whole byte.
+#[derive(Debug)]
+pub struct BitReader<'a, T: Read, B: ByteOrder> {
+    reader: &'a mut BinaryReader<T, B>,
+    bit_order: BitOrder,
+    current: u8,
+    bits_left: u32,
+}
+
+impl<'a, T: Read, B: ByteOrder> BitReader<'a, T, B> {
+    /// Creates a BitReader which reads bits from `reader` most significant bit first.
+    pub fn new(reader: &'a mut BinaryReader<T, B>) -> Self {
+        Self::with_bit_order(reader, BitOrder::default())
+    }
+
+    /// Creates a BitReader which reads bits from `reader` in the order `bit_order`.
+    pub fn with_bit_order(reader: &'a mut BinaryReader<T, B>, bit_order: BitOrder) -> Self {
+        Self { reader, bit_order, current: 0, bits_left: 0 }
+    }
+
+    /// Returns the order bits are read in.
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Reads a single bit.
+    pub fn read_bit(&mut self) -> Result<bool, DataDecodeError> {
+        if self.bits_left == 0 {
+            self.current = self.reader.read_byte()?;
+            self.bits_left = 8;
+        }
+        self.bits_left -= 1;
+        let bit = match self.bit_order {
+            BitOrder::MsbFirst => self.current >> self.bits_left,
+            BitOrder::LsbFirst => self.current >> (7 - self.bits_left),
+        };
+        Ok(bit & 1 != 0)
+    }
+
+    /// Reads `num_bits` bits and returns them as the low bits of a u64. The bits are assembled in
+    /// the order given by [BitReader::bit_order].
+    /// The bits read so far are consumed even if this fails partway through.
+    ///
+    /// # Panics
+    /// Panics if `num_bits` is greater than 64.
+    pub fn read_bits(&mut self, num_bits: u32) -> Result<u64, DataDecodeError> {
+        assert!(num_bits <= u64::BITS, "can't read more than 64 bits into a u64");
+        let mut output: u64 = 0;
+        for index in 0..num_bits {
+            let bit = u64::from(self.read_bit()?);
+            output = match self.bit_order {
+                BitOrder::MsbFirst => (output << 1) | bit,
+                BitOrder::LsbFirst => output | (bit << index),
+            };
+        }
+        Ok(output)
+    }
+
+    /// Discards the rest of the current byte, so the next bit is read from the start of a new
+    /// byte. Does nothing if no bits of the current byte have been read.
+    pub fn align_to_byte(&mut self) {
+        self.bits_left = 0;
+    }
+}
+
+/// Writes values packed at bit granularity rather than byte granularity, to a [BinaryWriter].
+///
+/// Bits are collected until a whole byte is ready, and then written to the [BinaryWriter]. The
+/// final partial byte is padded with zero bits and written by [BitWriter::flush], or when the
+/// BitWriter is dropped. A [BitReader] with the same [BitOrder] reads the padding as zeros and
+/// can skip it with [BitReader::align_to_byte].
+///
+/// Errors writing the final byte when the BitWriter is dropped are ignored, so call
+/// [BitWriter::flush] to handle them.
+pub struct BitWriter<'a, T: Write, B: ByteOrder> {
+    writer: &'a mut BinaryWriter<T, B>,
+    bit_order: BitOrder,
+    current: u8,
+    bits_used: u32,
+}
+
+impl<'a, T: Write, B: ByteOrder> BitWriter<'a, T, B> {
+    /// Creates a BitWriter which writes bits to `writer` most significant bit first.
+    pub fn new(writer: &'a mut BinaryWriter<T, B>) -> Self {
+        Self::with_bit_order(writer, BitOrder::default())
+    }
+
+    /// Creates a BitWriter which writes bits to `writer` in the order `bit_order`.
+    pub fn with_bit_order(writer: &'a mut BinaryWriter<T, B>, bit_order: BitOrder) -> Self {
+        Self { writer, bit_order, current: 0, bits_used: 0 }
+    }
+
+    /// Returns the order bits are written in.
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Writes a single bit. Returns the number of bytes this caused to be written to the
+    /// [BinaryWriter], which is 1 if it completed a byte and 0 otherwise.
+    pub fn write_bit(&mut self, bit: bool) -> io::Result<usize> {
+        let bit = u8::from(bit);
+        self.current |= match self.bit_order {
+            BitOrder::MsbFirst => bit << (7 - self.bits_used),
+            BitOrder::LsbFirst => bit << self.bits_used,
+        };
+        self.bits_used += 1;
+        if self.bits_used < 8 {
+            return Ok(0);
+        }
+        let byte = std::mem::take(&mut self.current);
+        self.bits_used = 0;
+        self.writer.write_byte(byte)
+    }
+
+    /// Writes the low `num_bits` bits of `value`, in the order given by [BitWriter::bit_order].
+    /// The other bits of `value` are ignored. Returns the number of whole bytes this caused to be
+    /// written to the [BinaryWriter].
+    ///
+    /// # Panics
+    /// Panics if `num_bits` is greater than 64.
+    pub fn write_bits(&mut self, value: u64, num_bits: u32) -> io::Result<usize> {
+        assert!(num_bits <= u64::BITS, "can't write more than the 64 bits of a u64");
+        let mut written = 0;
+        for index in 0..num_bits {
+            let shift = match self.bit_order {
+                BitOrder::MsbFirst => num_bits - 1 - index,
+                BitOrder::LsbFirst => index,
+            };
+            written += self.write_bit((value >> shift) & 1 != 0)?;
+        }
+        Ok(written)
+    }
+
+    /// Pads the current partial byte with zero bits and writes it, so the next bit starts a new
+    /// byte. Returns the number of bytes written, which is 0 if there was no partial byte.
+    pub fn flush(&mut self) -> io::Result<usize> {
+        if self.bits_used == 0 {
+            return Ok(0);
+        }
+        let byte = std::mem::take(&mut self.current);
+        self.bits_used = 0;
+        self.writer.write_byte(byte)
+    }
+}
+
+impl<T: Write, B: ByteOrder> Drop for BitWriter<'_, T, B> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads `bit_count` bools packed eight to a
+    /// byte, as written by `BitArray.CopyTo(byte[])` in C#: bit `i` is bit `i % 8` of byte
+    /// `i / 8`, counting from the least significant bit. The unused bits of the last byte are
+    /// ignored.
+    pub fn read_bit_array(&mut self, bit_count: usize) -> Result<Vec<bool>, DataDecodeError> {
+        let bytes = self.fill_buffer(bit_count.div_ceil(8))?;
+        Ok((0..bit_count).map(|index| bytes[index / 8] & (1 << (index % 8)) != 0).collect())
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Packs `bits` eight to a byte, in the order
+    /// read by [BinaryReader::read_bit_array]. The unused bits of the last byte are zero. The
+    /// number of bits isn't written. Returns the number of bytes written.
+    pub fn write_bit_array(&mut self, bits: &[bool]) -> io::Result<usize> {
+        let mut bytes: Vec<u8> = vec![0; bits.len().div_ceil(8)];
+        for (index, _) in bits.iter().enumerate().filter(|(_, bit)| **bit) {
+            bytes[index / 8] |= 1 << (index % 8);
+        }
+        self.write_bytes(&bytes)
+    }
+}
@@ -0,0 +1,76 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Makes any Read seekable by keeping everything read from it in memory. Wrapping a reader in a
+/// BufferedSeekReader lets a [BinaryReader](crate::BinaryReader) over a stream which can't seek,
+/// such as a pipe or a network stream, use its [Seek] implementation.
+///
+/// Reads pass through to the underlying reader until the first seek, but every byte read is kept.
+/// The first seek reads the rest of the underlying reader to its end, and from then on reads and
+/// seeks are served from memory. The whole stream ends up in memory, so this is only suitable for
+/// streams of a bounded, reasonable size, and the first seek never returns on a stream which
+/// doesn't end.
+#[derive(Debug)]
+pub struct BufferedSeekReader<R: Read> {
+    // `None` once the underlying reader has been read to its end
+    inner: Option<R>,
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read> BufferedSeekReader<R> {
+    /// Creates a new BufferedSeekReader which will read data from the provided Reader.
+    pub fn new(inner: R) -> Self {
+        Self { inner: Some(inner), data: Vec::new(), pos: 0 }
+    }
+
+    /// Returns the bytes read from the underlying reader so far, which is all of them once it has
+    /// been sought.
+    pub fn buffered(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Reads the rest of the underlying reader into memory, if it hasn't been already.
+    fn buffer_all(&mut self) -> io::Result<()> {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.read_to_end(&mut self.data)?;
+            self.inner = None;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for BufferedSeekReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.data.len()
+            && let Some(inner) = self.inner.as_mut() {
+            let read = inner.read(buf)?;
+            self.data.extend_from_slice(&buf[..read]);
+            self.pos += read;
+            return Ok(read);
+        }
+        let available = self.data.get(self.pos..).unwrap_or_default();
+        let read = available.len().min(buf.len());
+        buf[..read].copy_from_slice(&available[..read]);
+        self.pos += read;
+        Ok(read)
+    }
+}
+
+impl<R: Read> Seek for BufferedSeekReader<R> {
+    /// Reads the underlying reader to its end the first time it is called. Seeking past the end
+    /// is allowed, after which reads return no bytes. Seeking to before the start returns an
+    /// error of kind [io::ErrorKind::InvalidInput].
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.buffer_all()?;
+        let (base, offset) = match pos {
+            SeekFrom::Start(position) => (0, position.try_into().unwrap_or(i64::MAX)),
+            SeekFrom::End(offset) => (self.data.len(), offset),
+            SeekFrom::Current(offset) => (self.pos, offset),
+        };
+        let position = i64::try_from(base).ok().and_then(|base| base.checked_add(offset))
+            .and_then(|position| usize::try_from(position).ok())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek to a negative or overflowing position"))?;
+        self.pos = position;
+        Ok(position as u64)
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A fixed-size numeric primitive which can be converted to and from bytes in either byte order.
+///
+/// This trait is sealed, and is implemented for the integer and floating point primitives C#'s
+/// BinaryReader and BinaryWriter support.
+pub trait Primitive: sealed::Sealed + Copy + Debug {
+    /// The byte array representation of this type, e.g. `[u8; 4]` for `i32`.
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default + Copy;
+
+    /// Creates a value from its little-endian byte representation.
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+    /// Creates a value from its big-endian byte representation.
+    fn from_be_bytes(bytes: Self::Bytes) -> Self;
+    /// Returns the little-endian byte representation of this value.
+    fn to_le_bytes(self) -> Self::Bytes;
+    /// Returns the big-endian byte representation of this value.
+    fn to_be_bytes(self) -> Self::Bytes;
+
+    /// Returns the little-endian bytes of `data` without copying them, if that is possible on the
+    /// target.
+    #[doc(hidden)]
+    fn slice_as_le_bytes(_data: &[Self]) -> Option<&[u8]> {
+        None
+    }
+}
+
+macro_rules! impl_primitive {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl sealed::Sealed for $type {}
+
+            impl Primitive for $type {
+                type Bytes = [u8; size_of::<$type>()];
+
+                fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                    <$type>::from_le_bytes(bytes)
+                }
+
+                fn from_be_bytes(bytes: Self::Bytes) -> Self {
+                    <$type>::from_be_bytes(bytes)
+                }
+
+                fn to_le_bytes(self) -> Self::Bytes {
+                    <$type>::to_le_bytes(self)
+                }
+
+                fn to_be_bytes(self) -> Self::Bytes {
+                    <$type>::to_be_bytes(self)
+                }
+
+                // in memory, the values are already in little-endian order
+                #[cfg(all(feature = "bytemuck", target_endian = "little"))]
+                fn slice_as_le_bytes(data: &[Self]) -> Option<&[u8]> {
+                    Some(bytemuck::cast_slice(data))
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+#[cfg(feature = "f16")]
+impl_primitive!(f16);
+
+/// Decodes a value in the byte order `B` from a slice, or returns `None` if the slice isn't
+/// exactly as long as the value.
+pub(crate) fn from_slice<N: Primitive, B: ByteOrder>(bytes: &[u8]) -> Option<N> {
+    let mut array = N::Bytes::default();
+    if array.as_ref().len() != bytes.len() {
+        return None;
+    }
+    array.as_mut().copy_from_slice(bytes);
+    Some(B::from_bytes(array))
+}
+
+/// Returns the bit pattern of `value`, zero extended to 64 bits.
+pub(crate) fn to_bits<N: Primitive>(value: N) -> u64 {
+    value.to_le_bytes().as_ref().iter().rev().fold(0, |bits, &byte| (bits << 8) | u64::from(byte))
+}
+
+/// The byte order multi-byte numeric values are read and written in.
+///
+/// [BinaryReader](crate::BinaryReader) and [BinaryWriter](crate::BinaryWriter) are generic over
+/// a ByteOrder which defaults to [LittleEndian], the byte order C# always uses. Since the byte
+/// order is part of the type, selecting it has no runtime cost.
+///
+/// This trait is sealed, and is implemented by [LittleEndian] and [BigEndian].
+pub trait ByteOrder: sealed::Sealed + Copy + Eq + Hash + Debug + Default {
+    /// Converts bytes in this byte order to a value.
+    fn from_bytes<N: Primitive>(bytes: N::Bytes) -> N;
+    /// Converts a value to bytes in this byte order.
+    fn to_bytes<N: Primitive>(value: N) -> N::Bytes;
+}
+
+/// Least significant byte first. This is the byte order used by C#'s BinaryReader and
+/// BinaryWriter.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct LittleEndian;
+
+/// Most significant byte first, also known as network byte order.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct BigEndian;
+
+impl sealed::Sealed for LittleEndian {}
+impl sealed::Sealed for BigEndian {}
+
+impl ByteOrder for LittleEndian {
+    fn from_bytes<N: Primitive>(bytes: N::Bytes) -> N {
+        N::from_le_bytes(bytes)
+    }
+
+    fn to_bytes<N: Primitive>(value: N) -> N::Bytes {
+        value.to_le_bytes()
+    }
+}
+
+impl ByteOrder for BigEndian {
+    fn from_bytes<N: Primitive>(bytes: N::Bytes) -> N {
+        N::from_be_bytes(bytes)
+    }
+
+    fn to_bytes<N: Primitive>(value: N) -> N::Bytes {
+        value.to_be_bytes()
+    }
+}
@@ -0,0 +1,76 @@
+//! Free functions for encoding and decoding a single primitive value from a byte slice, without
+//! constructing a [BinaryReader](crate::BinaryReader) or [BinaryWriter](crate::BinaryWriter).
+//!
+//! The `read_*` functions return the decoded value along with the rest of the slice, and the
+//! `write_*` functions return the rest of the slice after the written value, so several values
+//! can be processed in sequence without allocating:
+//! ```
+//! # use csharp_binary_encoding::{bytes, InvalidDataError};
+//! let data: [u8; 6] = [0x2A, 0x00, 0x00, 0x00, 0x12, 0x34];
+//! let (number, rest) = bytes::read_i32_le(&data)?;
+//! let (short, rest) = bytes::read_u16_be(rest)?;
+//! assert_eq!((42, 0x1234), (number, short));
+//! assert!(rest.is_empty());
+//! # Ok::<(), InvalidDataError>(())
+//! ```
+use super::binaryreader::InvalidDataError;
+use super::byteorder::{from_slice, BigEndian, ByteOrder, LittleEndian, Primitive};
+
+fn read<N: Primitive, B: ByteOrder>(data: &[u8]) -> Result<(N, &[u8]), InvalidDataError> {
+    let (value, rest) = data.split_at_checked(size_of::<N::Bytes>()).ok_or(InvalidDataError::NotEnoughBytes)?;
+    let value = from_slice::<N, B>(value).ok_or(InvalidDataError::NotEnoughBytes)?;
+    Ok((value, rest))
+}
+
+fn write<N: Primitive, B: ByteOrder>(data: &mut [u8], value: N) -> Result<&mut [u8], InvalidDataError> {
+    let bytes = B::to_bytes(value);
+    let size = bytes.as_ref().len();
+    if data.len() < size {
+        return Err(InvalidDataError::NotEnoughBytes);
+    }
+    let (destination, rest) = data.split_at_mut(size);
+    destination.copy_from_slice(bytes.as_ref());
+    Ok(rest)
+}
+
+macro_rules! slice_functions {
+    ($type:ty, $read_le:ident, $read_be:ident, $write_le:ident, $write_be:ident) => {
+        #[doc = concat!("Decodes a little-endian `", stringify!($type), "` from the start of `data`, returning it and the remaining bytes.")]
+        /// Returns [InvalidDataError::NotEnoughBytes] if `data` is too short.
+        pub fn $read_le(data: &[u8]) -> Result<($type, &[u8]), InvalidDataError> {
+            read::<$type, LittleEndian>(data)
+        }
+
+        #[doc = concat!("Decodes a big-endian `", stringify!($type), "` from the start of `data`, returning it and the remaining bytes.")]
+        /// Returns [InvalidDataError::NotEnoughBytes] if `data` is too short.
+        pub fn $read_be(data: &[u8]) -> Result<($type, &[u8]), InvalidDataError> {
+            read::<$type, BigEndian>(data)
+        }
+
+        #[doc = concat!("Encodes a little-endian `", stringify!($type), "` into the start of `data`, returning the remaining bytes.")]
+        /// Returns [InvalidDataError::NotEnoughBytes] if `data` is too short, in which case
+        /// nothing is written.
+        pub fn $write_le(data: &mut [u8], value: $type) -> Result<&mut [u8], InvalidDataError> {
+            write::<$type, LittleEndian>(data, value)
+        }
+
+        #[doc = concat!("Encodes a big-endian `", stringify!($type), "` into the start of `data`, returning the remaining bytes.")]
+        /// Returns [InvalidDataError::NotEnoughBytes] if `data` is too short, in which case
+        /// nothing is written.
+        pub fn $write_be(data: &mut [u8], value: $type) -> Result<&mut [u8], InvalidDataError> {
+            write::<$type, BigEndian>(data, value)
+        }
+    };
+}
+
+slice_functions!(i16, read_i16_le, read_i16_be, write_i16_le, write_i16_be);
+slice_functions!(i32, read_i32_le, read_i32_be, write_i32_le, write_i32_be);
+slice_functions!(i64, read_i64_le, read_i64_be, write_i64_le, write_i64_be);
+slice_functions!(u16, read_u16_le, read_u16_be, write_u16_le, write_u16_be);
+slice_functions!(u32, read_u32_le, read_u32_be, write_u32_le, write_u32_be);
+slice_functions!(u64, read_u64_le, read_u64_be, write_u64_le, write_u64_be);
+slice_functions!(f32, read_f32_le, read_f32_be, write_f32_le, write_f32_be);
+slice_functions!(f64, read_f64_le, read_f64_be, write_f64_le, write_f64_be);
+
+#[cfg(feature = "f16")]
+slice_functions!(f16, read_f16_le, read_f16_be, write_f16_le, write_f16_be);
@@ -0,0 +1,71 @@
+use std::io::{self, Read, Write};
+use super::binaryreader::{BinaryReader, DataDecodeError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+/// A color with 8-bit alpha, red, green, and blue channels, like C#'s `System.Drawing.Color`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Color {
+    /// The alpha channel.
+    pub a: u8,
+    /// The red channel.
+    pub r: u8,
+    /// The green channel.
+    pub g: u8,
+    /// The blue channel.
+    pub b: u8,
+}
+
+impl Color {
+    /// Equivalent to the Color.FromArgb method in C# called with a single Int32. The alpha
+    /// channel is the most significant byte, followed by red, green, and blue.
+    pub fn from_argb(argb: u32) -> Self {
+        let [a, r, g, b] = argb.to_be_bytes();
+        Self { a, r, g, b }
+    }
+
+    /// Equivalent to the Color.ToArgb method in C#.
+    pub fn to_argb(self) -> u32 {
+        u32::from_be_bytes([self.a, self.r, self.g, self.b])
+    }
+}
+
+/// Selects how a [Color] is laid out in binary data.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum ColorLayout {
+    /// The value of `Color.ToArgb()` written as an Int32. The order of the bytes depends on the
+    /// byte order of the reader or writer.
+    #[default]
+    PackedArgb,
+    /// Four separate bytes, in the order alpha, red, green, blue.
+    Bytes,
+}
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads a [Color] laid out as specified by
+    /// `layout`.
+    pub fn read_color(&mut self, layout: ColorLayout) -> Result<Color, DataDecodeError> {
+        match layout {
+            ColorLayout::PackedArgb => Ok(Color::from_argb(self.read_u32()?)),
+            ColorLayout::Bytes => {
+                let [a, r, g, b] = self.read_array()?;
+                Ok(Color { a, r, g, b })
+            },
+        }
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Writes a [Color] laid out as specified by
+    /// `layout`.
+    pub fn write_color(&mut self, color: Color, layout: ColorLayout) -> io::Result<usize> {
+        match layout {
+            ColorLayout::PackedArgb => self.write_u32(color.to_argb()),
+            ColorLayout::Bytes => self.write_bytes(&[color.a, color.r, color.g, color.b]),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::io::{self, Read, Write};
+use super::binaryreader::{BinaryReader, DataDecodeError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+/// A complex number, like C#'s [`System.Numerics.Complex`].
+///
+/// C#'s BinaryWriter has no overload for Complex, so C# code writes the real part followed by the
+/// imaginary part as two Doubles.
+///
+/// With the `num-complex` feature this converts to and from [`num_complex::Complex64`].
+///
+/// [`System.Numerics.Complex`]: <https://learn.microsoft.com/en-us/dotnet/api/system.numerics.complex>
+/// [`num_complex::Complex64`]: <https://docs.rs/num-complex/latest/num_complex/type.Complex64.html>
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Complex {
+    /// Equivalent to the Complex.Real property in C#.
+    pub real: f64,
+    /// Equivalent to the Complex.Imaginary property in C#.
+    pub imaginary: f64,
+}
+
+impl Complex {
+    /// Equivalent to the Complex(Double, Double) constructor in C#.
+    pub fn new(real: f64, imaginary: f64) -> Self {
+        Self { real, imaginary }
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "num-complex")))]
+#[cfg(feature = "num-complex")]
+impl From<num_complex::Complex64> for Complex {
+    fn from(value: num_complex::Complex64) -> Self {
+        Self::new(value.re, value.im)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "num-complex")))]
+#[cfg(feature = "num-complex")]
+impl From<Complex> for num_complex::Complex64 {
+    fn from(value: Complex) -> Self {
+        Self::new(value.real, value.imaginary)
+    }
+}
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads a [Complex] written as its real part
+    /// followed by its imaginary part, each as a Double in the byte order of this reader.
+    pub fn read_complex(&mut self) -> Result<Complex, DataDecodeError> {
+        let real = self.read_f64()?;
+        let imaginary = self.read_f64()?;
+        Ok(Complex::new(real, imaginary))
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Writes a [Complex] as its real part followed
+    /// by its imaginary part, like `writer.Write(value.Real); writer.Write(value.Imaginary);` in
+    /// C#.
+    pub fn write_complex(&mut self, data: Complex) -> io::Result<usize> {
+        Ok(self.write_f64(data.real)? + self.write_f64(data.imaginary)?)
+    }
+}
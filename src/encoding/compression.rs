@@ -0,0 +1,61 @@
+use std::io::{self, Read, Write};
+use super::binaryreader::{BinaryReader, DataDecodeError, InvalidDataError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+use super::framing::LengthPrefix;
+
+const COMPRESSION_LEVEL: u8 = 6;
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads a section written as an Int32
+    /// uncompressed length, an Int32 compressed length, and that many bytes of raw deflate data,
+    /// as written by C#'s `DeflateStream`. Returns the decompressed bytes.
+    ///
+    /// Requires the `deflate` feature.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidCompressedData]) if the
+    /// data can't be decompressed or doesn't decompress to the declared length,
+    /// [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if either length is
+    /// negative, and [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) if
+    /// either length is larger than [BinaryReader::max_read_length]. The section is consumed even
+    /// if it is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "deflate")))]
+    pub fn read_compressed_section(&mut self) -> Result<Vec<u8>, DataDecodeError> {
+        let max = self.max_read_length();
+        let uncompressed_len = self.read_length_prefix(LengthPrefix::Int32, max)?;
+        let compressed_len = self.read_length_prefix(LengthPrefix::Int32, max)?;
+        let compressed = self.read_bytes(compressed_len)?;
+        // the limit stops a corrupt section from decompressing to more than it declares
+        let data = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, uncompressed_len)
+            .map_err(|_| InvalidDataError::InvalidCompressedData)?;
+        if data.len() != uncompressed_len {
+            return Err(InvalidDataError::InvalidCompressedData.into());
+        }
+        Ok(data)
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Compresses `data` with deflate and writes it
+    /// as read by [BinaryReader::read_compressed_section]. Returns the total number of bytes
+    /// written.
+    ///
+    /// Requires the `deflate` feature.
+    ///
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] without writing anything if either
+    /// length doesn't fit in an Int32.
+    #[cfg_attr(docsrs, doc(cfg(feature = "deflate")))]
+    pub fn write_compressed_section(&mut self, data: &[u8]) -> io::Result<usize> {
+        let compressed = miniz_oxide::deflate::compress_to_vec(data, COMPRESSION_LEVEL);
+        let too_long = |_| io::Error::new(io::ErrorKind::InvalidInput, "length does not fit in an Int32");
+        let uncompressed_len: i32 = data.len().try_into().map_err(too_long)?;
+        let compressed_len: i32 = compressed.len().try_into().map_err(too_long)?;
+        let written = self.write_i32(uncompressed_len)? + self.write_i32(compressed_len)?;
+        Ok(written + self.write_bytes(&compressed)?)
+    }
+}
@@ -0,0 +1,60 @@
+use std::io::Read;
+use super::binaryreader::{BinaryReader, DataDecodeError};
+use super::byteorder::ByteOrder;
+
+/// Implemented by types which can be read from a [BinaryReader].
+///
+/// Implementations are provided for the primitive types the C# BinaryReader can read, and for
+/// tuples of up to four readable types, which are read element by element in order.
+pub trait CsharpReadable: Sized {
+    /// Reads a value of this type from the reader.
+    fn read_from<T: Read, B: ByteOrder>(reader: &mut BinaryReader<T, B>) -> Result<Self, DataDecodeError>;
+}
+
+macro_rules! impl_csharp_readable {
+    ($($type:ty => $method:ident),* $(,)?) => {
+        $(
+            impl CsharpReadable for $type {
+                fn read_from<T: Read, B: ByteOrder>(reader: &mut BinaryReader<T, B>) -> Result<Self, DataDecodeError> {
+                    reader.$method()
+                }
+            }
+        )*
+    };
+}
+
+impl_csharp_readable! {
+    bool => read_boolean,
+    u8 => read_byte,
+    i8 => read_i8,
+    i16 => read_i16,
+    i32 => read_i32,
+    i64 => read_i64,
+    u16 => read_u16,
+    u32 => read_u32,
+    u64 => read_u64,
+    f32 => read_f32,
+    f64 => read_f64,
+    char => read_char,
+    String => read_string,
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "f16")))]
+#[cfg(feature = "f16")]
+impl_csharp_readable! {
+    f16 => read_f16,
+}
+
+macro_rules! impl_csharp_readable_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: CsharpReadable),+> CsharpReadable for ($($name,)+) {
+            fn read_from<T: Read, B: ByteOrder>(reader: &mut BinaryReader<T, B>) -> Result<Self, DataDecodeError> {
+                Ok(($($name::read_from(reader)?,)+))
+            }
+        }
+    };
+}
+
+impl_csharp_readable_tuple!(T1, T2);
+impl_csharp_readable_tuple!(T1, T2, T3);
+impl_csharp_readable_tuple!(T1, T2, T3, T4);
@@ -0,0 +1,72 @@
+use std::io::{self, Write};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+/// Implemented by types which can be written by a [BinaryWriter]. The counterpart of
+/// [CsharpReadable](crate::CsharpReadable).
+///
+/// Implementations are provided for the primitive types the C# BinaryWriter can write, and for
+/// tuples of up to four writable types, which are written element by element in order.
+pub trait CsharpWritable {
+    /// Writes this value to the writer, returning the number of bytes written.
+    fn write_to<T: Write, B: ByteOrder>(&self, writer: &mut BinaryWriter<T, B>) -> io::Result<usize>;
+}
+
+macro_rules! impl_csharp_writable {
+    ($($type:ty => $method:ident),* $(,)?) => {
+        $(
+            impl CsharpWritable for $type {
+                fn write_to<T: Write, B: ByteOrder>(&self, writer: &mut BinaryWriter<T, B>) -> io::Result<usize> {
+                    writer.$method(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_csharp_writable! {
+    bool => write_boolean,
+    u8 => write_byte,
+    i8 => write_i8,
+    i16 => write_i16,
+    i32 => write_i32,
+    i64 => write_i64,
+    u16 => write_u16,
+    u32 => write_u32,
+    u64 => write_u64,
+    f32 => write_f32,
+    f64 => write_f64,
+    char => write_char,
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "f16")))]
+#[cfg(feature = "f16")]
+impl_csharp_writable! {
+    f16 => write_f16,
+}
+
+impl CsharpWritable for str {
+    fn write_to<T: Write, B: ByteOrder>(&self, writer: &mut BinaryWriter<T, B>) -> io::Result<usize> {
+        writer.write_string(self)
+    }
+}
+
+impl CsharpWritable for String {
+    fn write_to<T: Write, B: ByteOrder>(&self, writer: &mut BinaryWriter<T, B>) -> io::Result<usize> {
+        writer.write_string(self)
+    }
+}
+
+macro_rules! impl_csharp_writable_tuple {
+    ($($name:ident => $index:tt),+) => {
+        impl<$($name: CsharpWritable),+> CsharpWritable for ($($name,)+) {
+            fn write_to<T: Write, B: ByteOrder>(&self, writer: &mut BinaryWriter<T, B>) -> io::Result<usize> {
+                Ok(0 $(+ self.$index.write_to(writer)?)+)
+            }
+        }
+    };
+}
+
+impl_csharp_writable_tuple!(T1 => 0, T2 => 1);
+impl_csharp_writable_tuple!(T1 => 0, T2 => 1, T3 => 2);
+impl_csharp_writable_tuple!(T1 => 0, T2 => 1, T3 => 2, T4 => 3);
@@ -0,0 +1,245 @@
+use std::io::{self, Read, Write};
+use super::binaryreader::{BinaryReader, DataDecodeError, InvalidDataError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+/// A time of day, like C#'s [`System.TimeOnly`], stored as the number of 100-nanosecond ticks
+/// since midnight.
+///
+/// [`System.TimeOnly`]: <https://learn.microsoft.com/en-us/dotnet/api/system.timeonly>
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct TimeOnly {
+    ticks: i64,
+}
+
+impl TimeOnly {
+    /// The number of ticks in a day. Valid tick counts are less than this.
+    pub const TICKS_PER_DAY: i64 = 864_000_000_000;
+
+    /// Equivalent to the TimeOnly(Int64) constructor in C#.
+    ///
+    /// # Errors
+    /// Returns [InvalidDataError::OutOfRange] if `ticks` is negative or not less than
+    /// [TimeOnly::TICKS_PER_DAY].
+    pub fn from_ticks(ticks: i64) -> Result<Self, InvalidDataError> {
+        if (0..Self::TICKS_PER_DAY).contains(&ticks) {
+            Ok(Self { ticks })
+        } else {
+            Err(InvalidDataError::OutOfRange)
+        }
+    }
+
+    /// Equivalent to the TimeOnly.Ticks property in C#.
+    pub fn ticks(self) -> i64 {
+        self.ticks
+    }
+}
+
+/// A date, like C#'s [`System.DateOnly`], stored as the number of days since January 1, 0001 in
+/// the proleptic Gregorian calendar.
+///
+/// [`System.DateOnly`]: <https://learn.microsoft.com/en-us/dotnet/api/system.dateonly>
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct DateOnly {
+    day_number: i32,
+}
+
+impl DateOnly {
+    /// The day number of December 31, 9999, the last date C# can represent.
+    pub const MAX_DAY_NUMBER: i32 = 3_652_058;
+
+    /// Equivalent to the DateOnly.FromDayNumber method in C#.
+    ///
+    /// # Errors
+    /// Returns [InvalidDataError::OutOfRange] if `day_number` is negative or greater than
+    /// [DateOnly::MAX_DAY_NUMBER].
+    pub fn from_day_number(day_number: i32) -> Result<Self, InvalidDataError> {
+        if (0..=Self::MAX_DAY_NUMBER).contains(&day_number) {
+            Ok(Self { day_number })
+        } else {
+            Err(InvalidDataError::OutOfRange)
+        }
+    }
+
+    /// Equivalent to the DateOnly.DayNumber property in C#.
+    pub fn day_number(self) -> i32 {
+        self.day_number
+    }
+}
+
+/// A date and time, like C#'s [`System.DateTime`], stored as the number of 100-nanosecond
+/// ticks since midnight on January 1, 0001 in the proleptic Gregorian calendar.
+///
+/// [`System.DateTime`]: <https://learn.microsoft.com/en-us/dotnet/api/system.datetime>
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct DateTime {
+    ticks: i64,
+}
+
+const TICKS_PER_MILLISECOND: i64 = 10_000;
+const MILLIS_PER_DAY: i64 = 86_400_000;
+// the number of days from January 1, 0001 to December 30, 1899, the OLE Automation epoch
+const DAYS_TO_1899: i64 = 693_593;
+const OA_DATE_OFFSET_MILLIS: i64 = DAYS_TO_1899 * MILLIS_PER_DAY;
+const OA_DATE_OFFSET_TICKS: i64 = DAYS_TO_1899 * TimeOnly::TICKS_PER_DAY;
+// OLE Automation dates start on January 1, 0100
+const OA_DATE_MIN_TICKS: i64 = (36_524 - 365) * TimeOnly::TICKS_PER_DAY;
+const OA_DATE_MIN: f64 = -657_435.0;
+const OA_DATE_MAX: f64 = 2_958_466.0;
+
+impl DateTime {
+    /// The ticks of December 31, 9999 at 23:59:59.9999999, the last moment C# can represent.
+    pub const MAX_TICKS: i64 = 3_155_378_975_999_999_999;
+
+    /// Equivalent to the DateTime(Int64) constructor in C#.
+    ///
+    /// # Errors
+    /// Returns [InvalidDataError::OutOfRange] if `ticks` is negative or greater than
+    /// [DateTime::MAX_TICKS].
+    pub fn from_ticks(ticks: i64) -> Result<Self, InvalidDataError> {
+        if (0..=Self::MAX_TICKS).contains(&ticks) {
+            Ok(Self { ticks })
+        } else {
+            Err(InvalidDataError::OutOfRange)
+        }
+    }
+
+    /// Equivalent to the DateTime.Ticks property in C#.
+    pub fn ticks(self) -> i64 {
+        self.ticks
+    }
+
+    // Implementation translated from the c# dotnet runtime's implementation of DateTime
+    // MIT Licensed by the .NET foundation, can be found at https://github.com/dotnet/runtime
+    /// Equivalent to the DateTime.FromOADate method in C#.
+    ///
+    /// An OLE Automation date is the number of days since midnight on December 30, 1899. The
+    /// fractional part is always the time of day, even for dates before 1899, so -1.25 is 6:00 on
+    /// December 29, 1899. The result is rounded to the nearest millisecond.
+    ///
+    /// # Errors
+    /// Returns [InvalidDataError::OutOfRange] if `oa_date` is not a finite number between
+    /// -657435 and 2958466, exclusive.
+    pub fn from_oa_date(oa_date: f64) -> Result<Self, InvalidDataError> {
+        if !(oa_date < OA_DATE_MAX && oa_date > OA_DATE_MIN) {
+            return Err(InvalidDataError::OutOfRange);
+        }
+        let mut millis = (oa_date * MILLIS_PER_DAY as f64 + if oa_date >= 0.0 { 0.5 } else { -0.5 }) as i64;
+        // a negative date still has a positive time of day, so flip the sign of the fraction
+        if millis < 0 {
+            millis -= (millis % MILLIS_PER_DAY) * 2;
+        }
+        millis += OA_DATE_OFFSET_MILLIS;
+        Self::from_ticks(millis * TICKS_PER_MILLISECOND)
+    }
+
+    // Implementation translated from the c# dotnet runtime's implementation of DateTime
+    // MIT Licensed by the .NET foundation, can be found at https://github.com/dotnet/runtime
+    /// Equivalent to the DateTime.ToOADate method in C#. See [DateTime::from_oa_date] for the
+    /// format. The time is truncated to the millisecond, toward December 30, 1899.
+    ///
+    /// Like C#, a value of 0 ticks is converted to 0.0 and any other value within the first day
+    /// is treated as a time on December 30, 1899. Returns `None` for other dates before January 1,
+    /// 0100, which can't be represented.
+    pub fn to_oa_date(self) -> Option<f64> {
+        let mut ticks = self.ticks;
+        if ticks == 0 {
+            return Some(0.0);
+        }
+        if ticks < TimeOnly::TICKS_PER_DAY {
+            ticks += OA_DATE_OFFSET_TICKS;
+        }
+        if ticks < OA_DATE_MIN_TICKS {
+            return None;
+        }
+        // like C#, subtract the offset first so the division rounds toward the epoch
+        let mut millis = (ticks - OA_DATE_OFFSET_TICKS) / TICKS_PER_MILLISECOND;
+        if millis < 0 {
+            let fraction = millis % MILLIS_PER_DAY;
+            if fraction != 0 {
+                millis -= (MILLIS_PER_DAY + fraction) * 2;
+            }
+        }
+        Some(millis as f64 / MILLIS_PER_DAY as f64)
+    }
+}
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads a [TimeOnly] written as its ticks in
+    /// an Int64.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::OutOfRange]) if the ticks are
+    /// not within a single day.
+    pub fn read_time_only(&mut self) -> Result<TimeOnly, DataDecodeError> {
+        Ok(TimeOnly::from_ticks(self.read_i64()?)?)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a [DateOnly] written as its day
+    /// number in an Int32.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::OutOfRange]) if the day number
+    /// is outside the range of dates C# supports.
+    pub fn read_date_only(&mut self) -> Result<DateOnly, DataDecodeError> {
+        Ok(DateOnly::from_day_number(self.read_i32()?)?)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a [DateTime] written as an OLE
+    /// Automation date in an f64, as C# code writes `writer.Write(date.ToOADate())`. See
+    /// [DateTime::from_oa_date] for the format.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::OutOfRange]) if the value is not
+    /// a valid OLE Automation date.
+    pub fn read_oa_date(&mut self) -> Result<DateTime, DataDecodeError> {
+        Ok(DateTime::from_oa_date(self.read_f64()?)?)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads the id of a time zone, as C# code
+    /// writes `writer.Write(zone.Id)` for a `TimeZoneInfo`. The id is read by
+    /// [BinaryReader::read_string] and returned as is: it may be a Windows id such as
+    /// "Pacific Standard Time" or an IANA id such as "America/Los_Angeles", depending on the
+    /// platform which wrote it, and isn't checked against either list.
+    ///
+    /// # Errors
+    /// Returns the same errors as [BinaryReader::read_string].
+    pub fn read_timezone_id(&mut self) -> Result<String, DataDecodeError> {
+        self.read_string()
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Writes the ticks of a [TimeOnly] as an
+    /// Int64, like `writer.Write(time.Ticks)` in C#.
+    pub fn write_time_only(&mut self, time: TimeOnly) -> io::Result<usize> {
+        self.write_i64(time.ticks())
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes the day number of a [DateOnly] as an
+    /// Int32, like `writer.Write(date.DayNumber)` in C#.
+    pub fn write_date_only(&mut self, date: DateOnly) -> io::Result<usize> {
+        self.write_i32(date.day_number())
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a [DateTime] as an OLE Automation
+    /// date in an f64, like `writer.Write(date.ToOADate())` in C#.
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] without writing anything if `date`
+    /// can't be represented as an OLE Automation date. See [DateTime::to_oa_date].
+    pub fn write_oa_date(&mut self, date: DateTime) -> io::Result<usize> {
+        let oa_date = date.to_oa_date().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "date can't be represented as an OLE Automation date")
+        })?;
+        self.write_f64(oa_date)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes the id of a time zone as written by
+    /// [BinaryWriter::write_string], like `writer.Write(zone.Id)` in C#.
+    pub fn write_timezone_id(&mut self, id: &str) -> io::Result<usize> {
+        self.write_string(id)
+    }
+}
@@ -0,0 +1,118 @@
+use std::io::{self, Read, Write};
+use super::binaryreader::{BinaryReader, DataDecodeError, InvalidDataError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+const SIGN_MASK: u32 = 0x8000_0000;
+const SCALE_MASK: u32 = 0x00FF_0000;
+const SCALE_SHIFT: u32 = 16;
+const MAX_SCALE: u8 = 28;
+const MAX_MANTISSA: u128 = (1 << 96) - 1;
+
+/// A 128-bit decimal floating point number, like C#'s [`System.Decimal`].
+///
+/// The value is a 96-bit integer mantissa divided by a power of ten between 0 and 28, with a
+/// separate sign. Values are stored exactly as C# stores them, so they aren't normalized: 1.0 and
+/// 1.00 are different values which compare unequal.
+///
+/// [`System.Decimal`]: <https://learn.microsoft.com/en-us/dotnet/api/system.decimal>
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Decimal {
+    lo: u32,
+    mid: u32,
+    hi: u32,
+    flags: u32,
+}
+
+impl Decimal {
+    /// Creates a decimal with the value `mantissa / 10^scale`.
+    ///
+    /// # Errors
+    /// Returns [InvalidDataError::InvalidDecimal] if `scale` is greater than 28, and
+    /// [InvalidDataError::OutOfRange] if the magnitude of `mantissa` doesn't fit in 96 bits.
+    pub fn new(mantissa: i128, scale: u8) -> Result<Self, InvalidDataError> {
+        if scale > MAX_SCALE {
+            return Err(InvalidDataError::InvalidDecimal);
+        }
+        let magnitude = mantissa.unsigned_abs();
+        if magnitude > MAX_MANTISSA {
+            return Err(InvalidDataError::OutOfRange);
+        }
+        let sign = if mantissa < 0 { SIGN_MASK } else { 0 };
+        Ok(Self {
+            lo: magnitude as u32,
+            mid: (magnitude >> 32) as u32,
+            hi: (magnitude >> 64) as u32,
+            flags: sign | (u32::from(scale) << SCALE_SHIFT),
+        })
+    }
+
+    /// Equivalent to the Decimal(Int32[]) constructor in C#. The bits are the low, middle and high
+    /// 32 bits of the mantissa followed by the flags, which hold the scale in bits 16 to 23 and the
+    /// sign in bit 31.
+    ///
+    /// # Errors
+    /// Returns [InvalidDataError::InvalidDecimal] if the scale is greater than 28 or any of the
+    /// other bits of the flags are set, like C#.
+    pub fn from_bits(bits: [u32; 4]) -> Result<Self, InvalidDataError> {
+        let [lo, mid, hi, flags] = bits;
+        if flags & !(SIGN_MASK | SCALE_MASK) != 0 || (flags & SCALE_MASK) > (u32::from(MAX_SCALE) << SCALE_SHIFT) {
+            return Err(InvalidDataError::InvalidDecimal);
+        }
+        Ok(Self { lo, mid, hi, flags })
+    }
+
+    /// Equivalent to the Decimal.GetBits method in C#. See [Decimal::from_bits] for the layout.
+    pub fn to_bits(self) -> [u32; 4] {
+        [self.lo, self.mid, self.hi, self.flags]
+    }
+
+    /// Returns the mantissa, including the sign.
+    pub fn mantissa(self) -> i128 {
+        let magnitude = (i128::from(self.hi) << 64) | (i128::from(self.mid) << 32) | i128::from(self.lo);
+        if self.is_negative() { -magnitude } else { magnitude }
+    }
+
+    /// Equivalent to the Decimal.Scale property in C#. The value is the mantissa divided by ten to
+    /// the power of the scale.
+    pub fn scale(self) -> u8 {
+        ((self.flags & SCALE_MASK) >> SCALE_SHIFT) as u8
+    }
+
+    /// Returns true if the sign bit is set. This can be true for a value of zero.
+    pub fn is_negative(self) -> bool {
+        self.flags & SIGN_MASK != 0
+    }
+}
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Equivalent to the ReadDecimal method in C#. Reads the four Int32s of
+    /// [Decimal::from_bits], each in the byte order of this reader.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidDecimal]) if the flags
+    /// aren't valid, where C# would throw an IOException. The bytes are consumed even if they
+    /// aren't.
+    pub fn read_decimal(&mut self) -> Result<Decimal, DataDecodeError> {
+        let lo = self.read_u32()?;
+        let mid = self.read_u32()?;
+        let hi = self.read_u32()?;
+        let flags = self.read_u32()?;
+        Ok(Decimal::from_bits([lo, mid, hi, flags])?)
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Equivalent to the Write method in C# called with an argument of type Decimal.
+    pub fn write_decimal(&mut self, data: Decimal) -> io::Result<usize> {
+        let mut written = 0;
+        for bits in data.to_bits() {
+            written += self.write_u32(bits)?;
+        }
+        Ok(written)
+    }
+}
@@ -0,0 +1,276 @@
+use std::io::{self, Read, Write};
+use super::binaryreader::{utf8_char_len, BinaryReader, DataDecodeError, InvalidDataError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::{from_slice, BigEndian, ByteOrder, LittleEndian};
+use super::textencoding::Encoding;
+
+/// A type tag identifying the type of a value in a self-describing stream.
+///
+/// The values match C#'s [`System.TypeCode`] enum, and each value is tagged with a single byte
+/// holding its type code. Only the type codes of values [DynamicValue] can hold are included.
+///
+/// [`System.TypeCode`]: <https://learn.microsoft.com/en-us/dotnet/api/system.typecode>
+#[non_exhaustive]
+#[repr(u8)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum TypeCode {
+    /// A null reference, which has no payload.
+    Empty = 0,
+    /// An array of values, written as a 7-bit encoded count followed by each value with its own
+    /// type code. Uses the type code C# gives to any object which isn't one of the other types.
+    Object = 1,
+    /// A bool, written as one byte.
+    Boolean = 3,
+    /// A char, written in the [Encoding] of the reader or writer.
+    Char = 4,
+    /// An i8.
+    SByte = 5,
+    /// A u8.
+    Byte = 6,
+    /// An i16.
+    Int16 = 7,
+    /// A u16.
+    UInt16 = 8,
+    /// An i32.
+    Int32 = 9,
+    /// A u32.
+    UInt32 = 10,
+    /// An i64.
+    Int64 = 11,
+    /// A u64.
+    UInt64 = 12,
+    /// An f32.
+    Single = 13,
+    /// An f64.
+    Double = 14,
+    /// A string with a 7-bit encoded length prefix.
+    String = 18,
+}
+
+impl TryFrom<u8> for TypeCode {
+    type Error = InvalidDataError;
+
+    /// Returns [InvalidDataError::UnknownTypeCode] if `value` isn't a supported type code.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Empty),
+            1 => Ok(Self::Object),
+            3 => Ok(Self::Boolean),
+            4 => Ok(Self::Char),
+            5 => Ok(Self::SByte),
+            6 => Ok(Self::Byte),
+            7 => Ok(Self::Int16),
+            8 => Ok(Self::UInt16),
+            9 => Ok(Self::Int32),
+            10 => Ok(Self::UInt32),
+            11 => Ok(Self::Int64),
+            12 => Ok(Self::UInt64),
+            13 => Ok(Self::Single),
+            14 => Ok(Self::Double),
+            18 => Ok(Self::String),
+            _ => Err(InvalidDataError::UnknownTypeCode),
+        }
+    }
+}
+
+/// A value of any of the types in [TypeCode], for reading and writing heterogeneous sequences
+/// without a schema.
+#[derive(Clone, PartialEq, Debug)]
+pub enum DynamicValue {
+    /// A null reference.
+    Empty,
+    /// An array of values, which may themselves be arrays.
+    Object(Vec<DynamicValue>),
+    /// A bool.
+    Boolean(bool),
+    /// A char.
+    Char(char),
+    /// An i8.
+    SByte(i8),
+    /// A u8.
+    Byte(u8),
+    /// An i16.
+    Int16(i16),
+    /// A u16.
+    UInt16(u16),
+    /// An i32.
+    Int32(i32),
+    /// A u32.
+    UInt32(u32),
+    /// An i64.
+    Int64(i64),
+    /// A u64.
+    UInt64(u64),
+    /// An f32.
+    Single(f32),
+    /// An f64.
+    Double(f64),
+    /// A string.
+    String(String),
+}
+
+impl DynamicValue {
+    /// Returns the type code this value is tagged with.
+    pub fn type_code(&self) -> TypeCode {
+        match self {
+            Self::Empty => TypeCode::Empty,
+            Self::Object(_) => TypeCode::Object,
+            Self::Boolean(_) => TypeCode::Boolean,
+            Self::Char(_) => TypeCode::Char,
+            Self::SByte(_) => TypeCode::SByte,
+            Self::Byte(_) => TypeCode::Byte,
+            Self::Int16(_) => TypeCode::Int16,
+            Self::UInt16(_) => TypeCode::UInt16,
+            Self::Int32(_) => TypeCode::Int32,
+            Self::UInt32(_) => TypeCode::UInt32,
+            Self::Int64(_) => TypeCode::Int64,
+            Self::UInt64(_) => TypeCode::UInt64,
+            Self::Single(_) => TypeCode::Single,
+            Self::Double(_) => TypeCode::Double,
+            Self::String(_) => TypeCode::String,
+        }
+    }
+}
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Gets the next byte as a [TypeCode] without
+    /// advancing the data stream, so the caller can decide how to read the value it tags.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::UnknownTypeCode]) if the type
+    /// code isn't supported. The byte is not consumed either way.
+    pub fn peek_type_code(&mut self) -> Result<TypeCode, DataDecodeError> {
+        Ok(TypeCode::try_from(self.peek_byte()?)?)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a one byte [TypeCode] followed by a
+    /// value of that type.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::UnknownTypeCode]) if the type
+    /// code isn't supported,
+    /// [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if an array has a
+    /// negative count, and [DataDecodeError]::InvalidData([InvalidDataError::MaxDepthExceeded])
+    /// if arrays are nested more deeply than [BinaryReader::max_depth].
+    pub fn read_dynamic(&mut self) -> Result<DynamicValue, DataDecodeError> {
+        let type_code = TypeCode::try_from(self.read_byte()?)?;
+        Ok(match type_code {
+            TypeCode::Empty => DynamicValue::Empty,
+            TypeCode::Object => DynamicValue::Object(self.nested(|reader| {
+                let count: usize = reader.read_7_bit_encoded_int()?.try_into()
+                    .map_err(|_| InvalidDataError::NegativeLength)?;
+                (0..count).map(|_| reader.read_dynamic()).collect()
+            })?),
+            TypeCode::Boolean => DynamicValue::Boolean(self.read_boolean()?),
+            TypeCode::Char => DynamicValue::Char(self.read_char()?),
+            TypeCode::SByte => DynamicValue::SByte(self.read_i8()?),
+            TypeCode::Byte => DynamicValue::Byte(self.read_byte()?),
+            TypeCode::Int16 => DynamicValue::Int16(self.read_i16()?),
+            TypeCode::UInt16 => DynamicValue::UInt16(self.read_u16()?),
+            TypeCode::Int32 => DynamicValue::Int32(self.read_i32()?),
+            TypeCode::UInt32 => DynamicValue::UInt32(self.read_u32()?),
+            TypeCode::Int64 => DynamicValue::Int64(self.read_i64()?),
+            TypeCode::UInt64 => DynamicValue::UInt64(self.read_u64()?),
+            TypeCode::Single => DynamicValue::Single(self.read_f32()?),
+            TypeCode::Double => DynamicValue::Double(self.read_f64()?),
+            TypeCode::String => DynamicValue::String(self.read_string()?),
+        })
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads the bytes of a value of the type
+    /// `type_code` without interpreting them, so data of types a program doesn't understand can be
+    /// copied through unchanged. The type code itself isn't read. Strings and arrays include their
+    /// length prefix, and each value in an array includes its type code.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidUtf8]) if the reader's
+    /// [Encoding] is [Encoding::Utf8] and a char doesn't start with a valid utf-8 lead byte, and
+    /// otherwise the same errors as [BinaryReader::read_dynamic]. Under the utf-16 encodings a
+    /// char takes 4 bytes if it starts with a high surrogate and 2 otherwise, and its code units
+    /// aren't otherwise checked.
+    pub fn read_raw_for_tag(&mut self, type_code: TypeCode) -> Result<Vec<u8>, DataDecodeError> {
+        match type_code {
+            TypeCode::Empty => Ok(Vec::new()),
+            TypeCode::Boolean | TypeCode::SByte | TypeCode::Byte => self.read_bytes(1),
+            TypeCode::Int16 | TypeCode::UInt16 => self.read_bytes(2),
+            TypeCode::Int32 | TypeCode::UInt32 | TypeCode::Single => self.read_bytes(4),
+            TypeCode::Int64 | TypeCode::UInt64 | TypeCode::Double => self.read_bytes(8),
+            TypeCode::Char => {
+                let length = match self.encoding() {
+                    Encoding::Utf8 => utf8_char_len(self.peek_byte()?).ok_or(InvalidDataError::InvalidUtf8)?,
+                    Encoding::Utf16 => self.peek_utf16_char_len::<B>()?,
+                    Encoding::Utf16BigEndian => self.peek_utf16_char_len::<BigEndian>()?,
+                    Encoding::Utf16LittleEndian => self.peek_utf16_char_len::<LittleEndian>()?,
+                    Encoding::Latin1 => 1,
+                };
+                self.read_bytes(length)
+            },
+            TypeCode::String => {
+                let prefix_len = self.peek_7_bit_encoded_int_len()?;
+                let mut output = self.peek_bytes(prefix_len)?.to_vec();
+                // decode the length like read_string, so the same strings are accepted
+                let length = self.read_string_length()?;
+                output.extend(self.read_bytes(length)?);
+                Ok(output)
+            },
+            TypeCode::Object => self.nested(|reader| {
+                let mut output = reader.read_raw_7_bit_encoded_int()?;
+                let count = BinaryReader::new(output.as_slice()).read_7_bit_encoded_int()?;
+                let count: usize = count.try_into().map_err(|_| InvalidDataError::NegativeLength)?;
+                for _ in 0..count {
+                    let type_code = reader.read_byte()?;
+                    output.push(type_code);
+                    output.extend(reader.read_raw_for_tag(TypeCode::try_from(type_code)?)?);
+                }
+                Ok(output)
+            }),
+        }
+    }
+
+    /// Returns the number of bytes the next utf-16 char takes up with code units in the byte order
+    /// `O`, without consuming it.
+    fn peek_utf16_char_len<O: ByteOrder>(&mut self) -> Result<usize, DataDecodeError> {
+        let unit: u16 = from_slice::<_, O>(self.peek_bytes(2)?).ok_or(InvalidDataError::NotEnoughBytes)?;
+        // a high surrogate is followed by the low surrogate which completes the pair
+        Ok(if (0xD800..0xDC00).contains(&unit) { 4 } else { 2 })
+    }
+
+    /// Reads the bytes of a 7-bit encoded int without decoding them.
+    fn read_raw_7_bit_encoded_int(&mut self) -> Result<Vec<u8>, DataDecodeError> {
+        let length = self.peek_7_bit_encoded_int_len()?;
+        self.read_bytes(length)
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Writes the [TypeCode] of `value` as one byte,
+    /// followed by the value itself.
+    pub fn write_dynamic(&mut self, value: &DynamicValue) -> io::Result<usize> {
+        let written = self.write_byte(value.type_code() as u8)?;
+        Ok(written + match value {
+            DynamicValue::Empty => 0,
+            DynamicValue::Object(values) => {
+                let mut written = self.write_7_bit_encoded_int(values.len().try_into().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "too many values in the array")
+                })?)?;
+                for value in values {
+                    written += self.write_dynamic(value)?;
+                }
+                written
+            },
+            DynamicValue::Boolean(data) => self.write_boolean(*data)?,
+            DynamicValue::Char(data) => self.write_char(*data)?,
+            DynamicValue::SByte(data) => self.write_i8(*data)?,
+            DynamicValue::Byte(data) => self.write_byte(*data)?,
+            DynamicValue::Int16(data) => self.write_i16(*data)?,
+            DynamicValue::UInt16(data) => self.write_u16(*data)?,
+            DynamicValue::Int32(data) => self.write_i32(*data)?,
+            DynamicValue::UInt32(data) => self.write_u32(*data)?,
+            DynamicValue::Int64(data) => self.write_i64(*data)?,
+            DynamicValue::UInt64(data) => self.write_u64(*data)?,
+            DynamicValue::Single(data) => self.write_f32(*data)?,
+            DynamicValue::Double(data) => self.write_f64(*data)?,
+            DynamicValue::String(data) => self.write_string(data)?,
+        })
+    }
+}
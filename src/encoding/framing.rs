@@ -0,0 +1,83 @@
+use std::io::Read;
+use std::iter::FusedIterator;
+use super::binaryreader::{BinaryReader, DataDecodeError};
+use super::byteorder::ByteOrder;
+
+/// Selects how a type tag is encoded by [BinaryWriter::write_tagged](crate::BinaryWriter::write_tagged)
+/// and decoded by [BinaryReader::read_tagged](crate::BinaryReader::read_tagged).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum TagEncoding {
+    /// A single byte. Tags must be between 0 and 255.
+    Byte,
+    /// A 4 byte Int32, as written by C#'s `Write(int)`.
+    #[default]
+    Int32,
+    /// A 7-bit encoded Int32, as written by C#'s `Write7BitEncodedInt`.
+    SevenBitEncodedInt,
+}
+
+/// Selects how a string which may be null is encoded by
+/// [BinaryWriter::write_nullable_string](crate::BinaryWriter::write_nullable_string) and decoded
+/// by [BinaryReader::read_nullable_string](crate::BinaryReader::read_nullable_string). Different
+/// .NET serializers mark null strings differently.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum NullStringEncoding {
+    /// A boolean byte which is 0 for null, followed by the string if the byte is 1, like
+    /// `writer.Write(s != null)` followed by `writer.Write(s)` in C#.
+    #[default]
+    PresenceFlag,
+    /// A 7-bit encoded length of -1 for null, or the string as C# writes it otherwise. Takes no
+    /// extra space for strings which aren't null, but null takes 5 bytes.
+    SentinelLength,
+}
+
+/// Selects how the length or element count in front of a sequence is encoded.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum LengthPrefix {
+    /// A 7-bit encoded Int32, as written by C#'s `Write7BitEncodedInt`. This is how C# prefixes
+    /// strings.
+    SevenBitEncodedInt,
+    /// A 4 byte Int32, as written by C#'s `Write(int)`. This is how C# code usually writes the
+    /// `Count` of a collection.
+    Int32,
+    /// A 2 byte UInt16, as written by C#'s `Write(ushort)`.
+    UInt16,
+    /// A 4 byte UInt32, as written by C#'s `Write(uint)`.
+    UInt32,
+}
+
+/// An iterator over the Int32 length-prefixed frames in a [BinaryReader], returned by
+/// [BinaryReader::frames].
+#[derive(Debug)]
+pub struct Frames<'a, T: Read, B: ByteOrder> {
+    reader: &'a mut BinaryReader<T, B>,
+    finished: bool,
+}
+
+impl<'a, T: Read, B: ByteOrder> Frames<'a, T, B> {
+    pub(crate) fn new(reader: &'a mut BinaryReader<T, B>) -> Self {
+        Self { reader, finished: false }
+    }
+}
+
+impl<T: Read, B: ByteOrder> Iterator for Frames<'_, T, B> {
+    type Item = Result<Vec<u8>, DataDecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let frame = match self.reader.is_at_eof() {
+            Ok(true) => {
+                self.finished = true;
+                return None;
+            },
+            Ok(false) => self.reader.read_frame(),
+            Err(e) => Err(e),
+        };
+        self.finished = frame.is_err();
+        Some(frame)
+    }
+}
+
+impl<T: Read, B: ByteOrder> FusedIterator for Frames<'_, T, B> {}
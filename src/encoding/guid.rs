@@ -0,0 +1,149 @@
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+use super::binaryreader::{BinaryReader, DataDecodeError, InvalidDataError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+use super::framing::LengthPrefix;
+
+const GUID_LEN: usize = 16;
+const GUID_STRING_LEN: usize = 36;
+const HYPHEN_POSITIONS: [usize; 4] = [8, 13, 18, 23];
+
+/// A globally unique identifier, like C#'s [`System.Guid`].
+///
+/// The bytes are stored in the order of C#'s `Guid.ToByteArray`, where the first three groups of
+/// the string form are little-endian, so the string `00112233-4455-6677-8899-aabbccddeeff` has
+/// the bytes `33 22 11 00 55 44 77 66 88 99 aa bb cc dd ee ff`.
+///
+/// [`System.Guid`]: <https://learn.microsoft.com/en-us/dotnet/api/system.guid>
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Guid {
+    bytes: [u8; 16],
+}
+
+impl Guid {
+    /// Equivalent to the Guid(Byte[]) constructor in C#.
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self { bytes }
+    }
+
+    /// Equivalent to the Guid.ToByteArray method in C#.
+    pub fn to_bytes(self) -> [u8; 16] {
+        self.bytes
+    }
+}
+
+/// Formats the Guid like C#'s `Guid.ToString()`, as 32 lowercase hex digits in groups of 8, 4, 4,
+/// 4 and 12 separated by hyphens.
+impl Display for Guid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let b = &self.bytes;
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-",
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            u16::from_le_bytes([b[4], b[5]]),
+            u16::from_le_bytes([b[6], b[7]]),
+        )?;
+        for byte in &b[8..10] {
+            write!(f, "{byte:02x}")?;
+        }
+        f.write_str("-")?;
+        for byte in &b[10..] {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the hyphenated form written by [Display](Guid#impl-Display-for-Guid), which is the "D"
+/// format of C#'s `Guid.Parse`. Hex digits may be upper or lowercase. Returns
+/// [InvalidDataError::InvalidGuid] for any other string.
+impl FromStr for Guid {
+    type Err = InvalidDataError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.as_bytes();
+        if s.len() != GUID_STRING_LEN || HYPHEN_POSITIONS.iter().any(|&i| s[i] != b'-') {
+            return Err(InvalidDataError::InvalidGuid);
+        }
+        let mut digits = s.iter().enumerate().filter(|(i, _)| !HYPHEN_POSITIONS.contains(i)).map(|(_, &c)| c);
+        let mut display_order: [u8; 16] = [0; 16];
+        for byte in display_order.iter_mut() {
+            let high = hex_value(digits.next())?;
+            let low = hex_value(digits.next())?;
+            *byte = (high << 4) | low;
+        }
+        // the first three groups are displayed most significant byte first
+        display_order[0..4].reverse();
+        display_order[4..6].reverse();
+        display_order[6..8].reverse();
+        Ok(Self::from_bytes(display_order))
+    }
+}
+
+fn hex_value(digit: Option<u8>) -> Result<u8, InvalidDataError> {
+    digit.and_then(|digit| char::from(digit).to_digit(16))
+        .map(|value| value as u8)
+        .ok_or(InvalidDataError::InvalidGuid)
+}
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads a [Guid] written as the 16 bytes of
+    /// `Guid.ToByteArray`, like `new Guid(reader.ReadBytes(16))` in C#. The layout is the same
+    /// regardless of the byte order of this reader.
+    pub fn read_guid(&mut self) -> Result<Guid, DataDecodeError> {
+        Ok(Guid::from_bytes(self.read_array()?))
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a [Guid] written as a string in its
+    /// hyphenated form, like `Guid.Parse(reader.ReadString())` in C#.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidGuid]) if the string isn't
+    /// a hyphenated Guid. See [Guid::from_str].
+    pub fn read_guid_string(&mut self) -> Result<Guid, DataDecodeError> {
+        Ok(self.read_string()?.parse()?)
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a count encoded as specified by
+    /// `prefix`, followed by that many Guids as read by [BinaryReader::read_guid]. All of the
+    /// Guids are read at once.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the count is
+    /// negative, and [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) if
+    /// the Guids take up more than [BinaryReader::max_read_length] bytes.
+    pub fn read_guid_array(&mut self, prefix: LengthPrefix) -> Result<Vec<Guid>, DataDecodeError> {
+        let count = self.read_length_prefix(prefix, usize::MAX)?;
+        let num_bytes = count.checked_mul(GUID_LEN).ok_or(InvalidDataError::LengthLimitExceeded)?;
+        let (guids, _) = self.fill_buffer(num_bytes)?.as_chunks();
+        Ok(guids.iter().map(|&bytes| Guid::from_bytes(bytes)).collect())
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Writes a [Guid] as the 16 bytes of
+    /// `Guid.ToByteArray`, like `writer.Write(guid.ToByteArray())` in C#.
+    pub fn write_guid(&mut self, data: Guid) -> io::Result<usize> {
+        self.write_bytes(&data.to_bytes())
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a [Guid] as a string in its
+    /// hyphenated form, like `writer.Write(guid.ToString())` in C#.
+    pub fn write_guid_string(&mut self, data: Guid) -> io::Result<usize> {
+        self.write_string(&data.to_string())
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes the number of Guids encoded as
+    /// specified by `prefix`, followed by each Guid as written by [BinaryWriter::write_guid], in
+    /// a single write. Returns the total number of bytes written.
+    pub fn write_guid_array(&mut self, prefix: LengthPrefix, data: &[Guid]) -> io::Result<usize> {
+        let written = self.write_length(prefix, data.len())?;
+        let bytes: Vec<u8> = data.iter().flat_map(|guid| guid.to_bytes()).collect();
+        Ok(written + self.write_bytes(&bytes)?)
+    }
+}
@@ -0,0 +1,41 @@
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use super::binaryreader::{BinaryReader, DataDecodeError, InvalidDataError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads an IP address written as its length as
+    /// a 7-bit encoded int, followed by the bytes returned by `IPAddress.GetAddressBytes()` in
+    /// C#. A length of 4 is an IPv4 address and 16 an IPv6 address. The bytes are always in
+    /// network order, regardless of the byte order of this reader.
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::OutOfRange]) if the length is
+    /// neither 4 nor 16, in which case only the length is consumed.
+    pub fn read_ip_address(&mut self) -> Result<IpAddr, DataDecodeError> {
+        match self.read_7_bit_encoded_int()? {
+            4 => Ok(Ipv4Addr::from(self.read_array::<4>()?).into()),
+            16 => Ok(Ipv6Addr::from(self.read_array::<16>()?).into()),
+            _ => Err(InvalidDataError::OutOfRange.into()),
+        }
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Writes an IP address as its length as a
+    /// 7-bit encoded int followed by its bytes in network order, as C# code commonly writes the
+    /// result of `IPAddress.GetAddressBytes()`. Read back by
+    /// [BinaryReader::read_ip_address](crate::BinaryReader::read_ip_address).
+    /// Returns the total number of bytes written.
+    pub fn write_ip_address(&mut self, address: IpAddr) -> io::Result<usize> {
+        let bytes = match address {
+            IpAddr::V4(address) => address.octets().to_vec(),
+            IpAddr::V6(address) => address.octets().to_vec(),
+        };
+        let written = self.write_7_bit_encoded_int(bytes.len() as i32)?;
+        Ok(written + self.write_bytes(&bytes)?)
+    }
+}
@@ -0,0 +1,42 @@
+use std::io::{self, Read, Write};
+use serde::{Serialize, de::DeserializeOwned};
+use super::binaryreader::{BinaryReader, DataDecodeError, InvalidDataError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads a string as read by
+    /// [BinaryReader::read_string] and deserializes it as JSON, as C# code commonly writes
+    /// `writer.Write(JsonSerializer.Serialize(value))`.
+    ///
+    /// Requires the `serde_json` feature.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidJson]) if the string
+    /// isn't valid JSON or doesn't match `R`, along with the errors of
+    /// [BinaryReader::read_string]. The string is consumed even if it is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde_json")))]
+    pub fn read_json<R: DeserializeOwned>(&mut self) -> Result<R, DataDecodeError> {
+        let string = self.read_string()?;
+        serde_json::from_str(&string).map_err(|_| InvalidDataError::InvalidJson.into())
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Serializes `value` as JSON and writes it as
+    /// written by [BinaryWriter::write_string], to be read back by
+    /// [BinaryReader::read_json](crate::BinaryReader::read_json).
+    /// Returns an error of kind [io::ErrorKind::InvalidInput] without writing anything if `value`
+    /// can't be serialized as JSON.
+    ///
+    /// Requires the `serde_json` feature.
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde_json")))]
+    pub fn write_json<V: Serialize + ?Sized>(&mut self, value: &V) -> io::Result<usize> {
+        let string = serde_json::to_string(value).map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        self.write_string(&string)
+    }
+}
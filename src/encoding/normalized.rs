@@ -0,0 +1,82 @@
+use std::io::{self, Read, Write};
+use super::binaryreader::{BinaryReader, DataDecodeError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+/// Converts `value` to the nearest step of an unsigned normalized integer with a maximum of `max`.
+/// NaN is converted to 0 and values outside 0.0 to 1.0 are clamped.
+fn quantize_unorm(value: f32, max: f32) -> f32 {
+    if value.is_nan() {
+        return 0.0;
+    }
+    (value.clamp(0.0, 1.0) * max).round()
+}
+
+/// Converts `value` to the nearest step of a signed normalized integer with a maximum of `max`.
+/// NaN is converted to 0 and values outside -1.0 to 1.0 are clamped.
+fn quantize_snorm(value: f32, max: f32) -> f32 {
+    if value.is_nan() {
+        return 0.0;
+    }
+    (value.clamp(-1.0, 1.0) * max).round()
+}
+
+/// Functions for normalized integers, which graphics formats use to store a value between 0.0 and
+/// 1.0 (unsigned) or -1.0 and 1.0 (signed) as evenly spaced integer steps.
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads a byte and maps it to a value between
+    /// 0.0 and 1.0, where 255 is 1.0.
+    pub fn read_unorm8(&mut self) -> Result<f32, DataDecodeError> {
+        Ok(f32::from(self.read_byte()?) / f32::from(u8::MAX))
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads a UInt16 and maps it to a value between
+    /// 0.0 and 1.0, where 65535 is 1.0.
+    pub fn read_unorm16(&mut self) -> Result<f32, DataDecodeError> {
+        Ok(f32::from(self.read_u16()?) / f32::from(u16::MAX))
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads an SByte and maps it to a value between
+    /// -1.0 and 1.0, where 127 is 1.0. Both -128 and -127 are -1.0.
+    pub fn read_snorm8(&mut self) -> Result<f32, DataDecodeError> {
+        Ok((f32::from(self.read_i8()?) / f32::from(i8::MAX)).max(-1.0))
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads an Int16 and maps it to a value between
+    /// -1.0 and 1.0, where 32767 is 1.0. Both -32768 and -32767 are -1.0.
+    pub fn read_snorm16(&mut self) -> Result<f32, DataDecodeError> {
+        Ok((f32::from(self.read_i16()?) / f32::from(i16::MAX)).max(-1.0))
+    }
+}
+
+/// Functions for normalized integers. Values are rounded to the nearest step, values outside the
+/// normalized range are clamped to it, and NaN is written as 0.
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Writes a value between 0.0 and 1.0 as a byte,
+    /// where 1.0 is 255.
+    pub fn write_unorm8(&mut self, data: f32) -> io::Result<usize> {
+        self.write_byte(quantize_unorm(data, f32::from(u8::MAX)) as u8)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a value between 0.0 and 1.0 as a
+    /// UInt16, where 1.0 is 65535.
+    pub fn write_unorm16(&mut self, data: f32) -> io::Result<usize> {
+        self.write_u16(quantize_unorm(data, f32::from(u16::MAX)) as u16)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a value between -1.0 and 1.0 as an
+    /// SByte, where 1.0 is 127 and -1.0 is -127.
+    pub fn write_snorm8(&mut self, data: f32) -> io::Result<usize> {
+        self.write_i8(quantize_snorm(data, f32::from(i8::MAX)) as i8)
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes a value between -1.0 and 1.0 as an
+    /// Int16, where 1.0 is 32767 and -1.0 is -32767.
+    pub fn write_snorm16(&mut self, data: f32) -> io::Result<usize> {
+        self.write_i16(quantize_snorm(data, f32::from(i16::MAX)) as i16)
+    }
+}
@@ -0,0 +1,338 @@
+use std::io::{self, Read, Write};
+use super::binaryreader::{BinaryReader, DataDecodeError, InvalidDataError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::{from_slice, ByteOrder, Primitive};
+
+/// The type of one field in a record, for reading records described at runtime rather than by
+/// Rust types. See [BinaryReader::read_record].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FieldType {
+    /// A bool, read with [BinaryReader::read_boolean].
+    Boolean,
+    /// A u8, read with [BinaryReader::read_byte].
+    U8,
+    /// An i8, read with [BinaryReader::read_i8].
+    I8,
+    /// An i16, read with [BinaryReader::read_i16].
+    I16,
+    /// A u16, read with [BinaryReader::read_u16].
+    U16,
+    /// An i32, read with [BinaryReader::read_i32].
+    I32,
+    /// A u32, read with [BinaryReader::read_u32].
+    U32,
+    /// An i64, read with [BinaryReader::read_i64].
+    I64,
+    /// A u64, read with [BinaryReader::read_u64].
+    U64,
+    /// An f32, read with [BinaryReader::read_f32].
+    F32,
+    /// An f64, read with [BinaryReader::read_f64].
+    F64,
+    /// A char, read with [BinaryReader::read_char].
+    Char,
+    /// A string, read with [BinaryReader::read_string].
+    String,
+    /// A fixed number of raw bytes, read with [BinaryReader::read_bytes].
+    Bytes(usize),
+    /// An i32, read with [BinaryReader::read_7_bit_encoded_int].
+    SevenBitEncodedInt,
+    /// An i64, read with [BinaryReader::read_7_bit_encoded_int64].
+    SevenBitEncodedInt64,
+}
+
+impl FieldType {
+    /// Returns the number of bytes a field of this type always takes up, or `None` if it depends
+    /// on the value.
+    pub fn fixed_size(self) -> Option<usize> {
+        self.as_fixed().map(FixedField::size)
+    }
+
+    /// Returns this type as a [FixedField], or `None` if its size depends on the value.
+    fn as_fixed(self) -> Option<FixedField> {
+        match self {
+            Self::Boolean => Some(FixedField::Boolean),
+            Self::U8 => Some(FixedField::U8),
+            Self::I8 => Some(FixedField::I8),
+            Self::I16 => Some(FixedField::I16),
+            Self::U16 => Some(FixedField::U16),
+            Self::I32 => Some(FixedField::I32),
+            Self::U32 => Some(FixedField::U32),
+            Self::I64 => Some(FixedField::I64),
+            Self::U64 => Some(FixedField::U64),
+            Self::F32 => Some(FixedField::F32),
+            Self::F64 => Some(FixedField::F64),
+            Self::Bytes(num_bytes) => Some(FixedField::Bytes(num_bytes)),
+            Self::Char | Self::String | Self::SevenBitEncodedInt | Self::SevenBitEncodedInt64 => None,
+        }
+    }
+}
+
+/// The [FieldType]s with a fixed size, so a plan can only hold those in its fixed runs.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+enum FixedField {
+    Boolean,
+    U8,
+    I8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    Bytes(usize),
+}
+
+impl FixedField {
+    /// Returns the number of bytes a field of this type takes up.
+    fn size(self) -> usize {
+        match self {
+            Self::Boolean | Self::U8 | Self::I8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::I64 | Self::U64 | Self::F64 => 8,
+            Self::Bytes(num_bytes) => num_bytes,
+        }
+    }
+}
+
+/// A record schema prepared for reading many records with [BinaryReader::read_planned_record].
+///
+/// Each run of consecutive fixed-size fields is read with a single read and decoded straight from
+/// the reader's buffer, instead of with one read per field. Fields with a variable size are read
+/// one at a time, as by [BinaryReader::read_field].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct RecordPlan {
+    segments: Vec<Segment>,
+    num_fields: usize,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Segment {
+    /// Consecutive fixed-size fields, and the total size of the run.
+    Fixed { fields: Vec<FixedField>, size: usize },
+    Variable(FieldType),
+}
+
+impl RecordPlan {
+    /// Prepares a plan for reading records described by `schema`. A run of fixed-size fields
+    /// whose total size doesn't fit in a usize is split into several runs.
+    pub fn new(schema: &[FieldType]) -> Self {
+        let mut segments: Vec<Segment> = Vec::new();
+        for &field_type in schema {
+            let Some(field) = field_type.as_fixed() else {
+                segments.push(Segment::Variable(field_type));
+                continue;
+            };
+            if let Some(Segment::Fixed { fields, size }) = segments.last_mut()
+                && let Some(new_size) = size.checked_add(field.size()) {
+                fields.push(field);
+                *size = new_size;
+            } else {
+                segments.push(Segment::Fixed { fields: vec![field], size: field.size() });
+            }
+        }
+        Self { segments, num_fields: schema.len() }
+    }
+
+    /// Returns the number of bytes every record takes up, or `None` if the schema has a field
+    /// with a variable size or the total size doesn't fit in a usize.
+    pub fn fixed_size(&self) -> Option<usize> {
+        self.segments.iter().try_fold(0usize, |total, segment| match segment {
+            Segment::Fixed { size, .. } => total.checked_add(*size),
+            Segment::Variable(_) => None,
+        })
+    }
+}
+
+/// Decodes a primitive from exactly as many bytes as it takes up.
+fn decode_primitive<N: Primitive, B: ByteOrder>(bytes: &[u8]) -> Result<N, DataDecodeError> {
+    from_slice::<N, B>(bytes).ok_or_else(|| InvalidDataError::NotEnoughBytes.into())
+}
+
+/// Decodes a fixed-size field from exactly as many bytes as it takes up, checking floats like
+/// [BinaryReader::read_f32] does when `strict_floats` is set.
+fn decode_fixed_field<B: ByteOrder>(field: FixedField, bytes: &[u8], strict_floats: bool) -> Result<FieldValue, DataDecodeError> {
+    let value = match field {
+        FixedField::Boolean => FieldValue::Boolean(decode_primitive::<u8, B>(bytes)? != 0),
+        FixedField::U8 => FieldValue::U8(decode_primitive::<_, B>(bytes)?),
+        FixedField::I8 => FieldValue::I8(decode_primitive::<_, B>(bytes)?),
+        FixedField::I16 => FieldValue::I16(decode_primitive::<_, B>(bytes)?),
+        FixedField::U16 => FieldValue::U16(decode_primitive::<_, B>(bytes)?),
+        FixedField::I32 => FieldValue::I32(decode_primitive::<_, B>(bytes)?),
+        FixedField::U32 => FieldValue::U32(decode_primitive::<_, B>(bytes)?),
+        FixedField::I64 => FieldValue::I64(decode_primitive::<_, B>(bytes)?),
+        FixedField::U64 => FieldValue::U64(decode_primitive::<_, B>(bytes)?),
+        FixedField::F32 => FieldValue::F32(decode_primitive::<_, B>(bytes)?),
+        FixedField::F64 => FieldValue::F64(decode_primitive::<_, B>(bytes)?),
+        FixedField::Bytes(_) => FieldValue::Bytes(bytes.to_vec()),
+    };
+    let is_finite = match value {
+        FieldValue::F32(float) => float.is_finite(),
+        FieldValue::F64(float) => float.is_finite(),
+        _ => true,
+    };
+    if strict_floats && !is_finite {
+        return Err(InvalidDataError::NonFiniteFloat.into());
+    }
+    Ok(value)
+}
+
+/// The value of one field in a record, as read for the matching [FieldType].
+#[derive(Clone, PartialEq, Debug)]
+pub enum FieldValue {
+    /// A bool.
+    Boolean(bool),
+    /// A u8.
+    U8(u8),
+    /// An i8.
+    I8(i8),
+    /// An i16.
+    I16(i16),
+    /// A u16.
+    U16(u16),
+    /// An i32.
+    I32(i32),
+    /// A u32.
+    U32(u32),
+    /// An i64.
+    I64(i64),
+    /// A u64.
+    U64(u64),
+    /// An f32.
+    F32(f32),
+    /// An f64.
+    F64(f64),
+    /// A char.
+    Char(char),
+    /// A string.
+    String(String),
+    /// Raw bytes.
+    Bytes(Vec<u8>),
+    /// A 7-bit encoded i32.
+    SevenBitEncodedInt(i32),
+    /// A 7-bit encoded i64.
+    SevenBitEncodedInt64(i64),
+}
+
+impl FieldValue {
+    /// Returns the type of this field.
+    pub fn field_type(&self) -> FieldType {
+        match self {
+            Self::Boolean(_) => FieldType::Boolean,
+            Self::U8(_) => FieldType::U8,
+            Self::I8(_) => FieldType::I8,
+            Self::I16(_) => FieldType::I16,
+            Self::U16(_) => FieldType::U16,
+            Self::I32(_) => FieldType::I32,
+            Self::U32(_) => FieldType::U32,
+            Self::I64(_) => FieldType::I64,
+            Self::U64(_) => FieldType::U64,
+            Self::F32(_) => FieldType::F32,
+            Self::F64(_) => FieldType::F64,
+            Self::Char(_) => FieldType::Char,
+            Self::String(_) => FieldType::String,
+            Self::Bytes(bytes) => FieldType::Bytes(bytes.len()),
+            Self::SevenBitEncodedInt(_) => FieldType::SevenBitEncodedInt,
+            Self::SevenBitEncodedInt64(_) => FieldType::SevenBitEncodedInt64,
+        }
+    }
+}
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads a single field of the given type.
+    pub fn read_field(&mut self, field_type: FieldType) -> Result<FieldValue, DataDecodeError> {
+        Ok(match field_type {
+            FieldType::Boolean => FieldValue::Boolean(self.read_boolean()?),
+            FieldType::U8 => FieldValue::U8(self.read_byte()?),
+            FieldType::I8 => FieldValue::I8(self.read_i8()?),
+            FieldType::I16 => FieldValue::I16(self.read_i16()?),
+            FieldType::U16 => FieldValue::U16(self.read_u16()?),
+            FieldType::I32 => FieldValue::I32(self.read_i32()?),
+            FieldType::U32 => FieldValue::U32(self.read_u32()?),
+            FieldType::I64 => FieldValue::I64(self.read_i64()?),
+            FieldType::U64 => FieldValue::U64(self.read_u64()?),
+            FieldType::F32 => FieldValue::F32(self.read_f32()?),
+            FieldType::F64 => FieldValue::F64(self.read_f64()?),
+            FieldType::Char => FieldValue::Char(self.read_char()?),
+            FieldType::String => FieldValue::String(self.read_string()?),
+            FieldType::Bytes(num_bytes) => FieldValue::Bytes(self.read_bytes(num_bytes)?),
+            FieldType::SevenBitEncodedInt => FieldValue::SevenBitEncodedInt(self.read_7_bit_encoded_int()?),
+            FieldType::SevenBitEncodedInt64 => FieldValue::SevenBitEncodedInt64(self.read_7_bit_encoded_int64()?),
+        })
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads one record described by `schema`,
+    /// reading each field in order. Useful for tools which inspect files using a schema loaded at
+    /// runtime.
+    pub fn read_record(&mut self, schema: &[FieldType]) -> Result<Vec<FieldValue>, DataDecodeError> {
+        schema.iter()
+            .map(|field_type| self.read_field(*field_type))
+            .collect()
+    }
+
+    /// Doesn't correspond to any specific c# method. Reads one record described by `plan`, giving
+    /// the same values as [BinaryReader::read_record] with the schema the plan was made from, but
+    /// faster when the schema has runs of fixed-size fields. Each such run is consumed in full even
+    /// if decoding one of its fields fails.
+    pub fn read_planned_record(&mut self, plan: &RecordPlan) -> Result<Vec<FieldValue>, DataDecodeError> {
+        let strict_floats = self.strict_floats();
+        let mut values = Vec::with_capacity(plan.num_fields);
+        for segment in &plan.segments {
+            match segment {
+                Segment::Fixed { fields, size } => {
+                    let mut bytes = self.fill_buffer(*size)?;
+                    for &field in fields {
+                        let (field_bytes, rest) = bytes.split_at(field.size());
+                        values.push(decode_fixed_field::<B>(field, field_bytes, strict_floats)?);
+                        bytes = rest;
+                    }
+                },
+                Segment::Variable(field_type) => values.push(self.read_field(*field_type)?),
+            }
+        }
+        Ok(values)
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Writes a single field with the method
+    /// matching its type.
+    pub fn write_field(&mut self, value: &FieldValue) -> io::Result<usize> {
+        match value {
+            FieldValue::Boolean(data) => self.write_boolean(*data),
+            FieldValue::U8(data) => self.write_byte(*data),
+            FieldValue::I8(data) => self.write_i8(*data),
+            FieldValue::I16(data) => self.write_i16(*data),
+            FieldValue::U16(data) => self.write_u16(*data),
+            FieldValue::I32(data) => self.write_i32(*data),
+            FieldValue::U32(data) => self.write_u32(*data),
+            FieldValue::I64(data) => self.write_i64(*data),
+            FieldValue::U64(data) => self.write_u64(*data),
+            FieldValue::F32(data) => self.write_f32(*data),
+            FieldValue::F64(data) => self.write_f64(*data),
+            FieldValue::Char(data) => self.write_char(*data),
+            FieldValue::String(data) => self.write_string(data),
+            FieldValue::Bytes(data) => self.write_bytes(data),
+            FieldValue::SevenBitEncodedInt(data) => self.write_7_bit_encoded_int(*data),
+            FieldValue::SevenBitEncodedInt64(data) => self.write_7_bit_encoded_int64(*data),
+        }
+    }
+
+    /// Doesn't correspond to any specific c# method. Writes each field of a record in order, so
+    /// that it can be read back with [BinaryReader::read_record] using the schema given by each
+    /// value's [FieldValue::field_type]. Returns the total number of bytes written.
+    pub fn write_record(&mut self, values: &[FieldValue]) -> io::Result<usize> {
+        let mut written = 0;
+        for value in values {
+            written += self.write_field(value)?;
+        }
+        Ok(written)
+    }
+}
@@ -0,0 +1,139 @@
+//! Helpers for testing [CsharpReadable] and [CsharpWritable] implementations, and for fuzzing
+//! [BinaryReader].
+//!
+//! Requires the `testutil` feature.
+//! ```
+//! # use csharp_binary_encoding::testutil::round_trip;
+//! assert_eq!((7, "meow".to_string()), round_trip(&(7_i32, "meow".to_string())));
+//! ```
+use super::binaryreader::{BinaryReader, DataDecodeError};
+use super::binarywriter::BinaryWriter;
+use super::bits::BitReader;
+use super::color::ColorLayout;
+use super::csharpreadable::CsharpReadable;
+use super::csharpwritable::CsharpWritable;
+use super::framing::LengthPrefix;
+use super::schema::FieldType;
+use super::textencoding::Encoding;
+
+/// Writes `value` to an in-memory buffer with [BinaryWriter::write], then reads it back with
+/// [BinaryReader::read] and returns the result, so it can be compared with `value`.
+///
+/// # Panics
+/// Panics if writing or reading fails, if the number of bytes written doesn't match the number
+/// reported, or if reading the value doesn't consume every byte that was written.
+pub fn round_trip<T: CsharpReadable + CsharpWritable>(value: &T) -> T {
+    let mut data: Vec<u8> = Vec::new();
+    let mut writer = BinaryWriter::new(&mut data);
+    let written = writer.write(value).expect("writing to a Vec shouldn't fail");
+    assert_eq!(written, data.len(), "reported length doesn't match the bytes written");
+
+    let mut reader = BinaryReader::new(data.as_slice());
+    let read = match reader.read() {
+        Ok(read) => read,
+        Err(error) => panic!("failed to read back the written value: {error}"),
+    };
+    assert_eq!(data.len() as u64, reader.num_bytes_read(), "not all written bytes were read back");
+    read
+}
+
+/// A read performed repeatedly by [try_parse_all].
+type ParseStep = fn(&mut BinaryReader<&[u8]>) -> Result<(), DataDecodeError>;
+
+const PARSE_STEPS: &[ParseStep] = &[
+    |r| r.read_byte().map(drop),
+    |r| r.read_boolean().map(drop),
+    |r| r.read_i16().map(drop),
+    |r| r.read_i32().map(drop),
+    |r| r.read_i64().map(drop),
+    |r| r.read_u64_as_usize().map(drop),
+    |r| r.read_i64_as_isize().map(drop),
+    |r| r.read_f32().map(drop),
+    |r| r.read_f64().map(drop),
+    |r| r.read_7_bit_encoded_int().map(drop),
+    |r| r.read_7_bit_encoded_int64().map(drop),
+    |r| r.peek_7_bit_encoded_int_len().and_then(|len| r.read_bytes(len)).map(drop),
+    |r| r.read_zigzag_varint32().map(drop),
+    |r| r.read_zigzag_varint64().map(drop),
+    |r| r.read_leb128_i32().map(drop),
+    |r| r.read_leb128_i64().map(drop),
+    |r| r.read_char().map(drop),
+    |r| r.read_string().map(drop),
+    |r| r.read_string_u32_prefixed().map(drop),
+    |r| r.read_string_by_char_count(LengthPrefix::SevenBitEncodedInt).map(drop),
+    |r| r.read_string_detect(LengthPrefix::SevenBitEncodedInt).map(drop),
+    |r| r.read_interned_string().map(drop),
+    |r| r.read_cstring().map(drop),
+    |r| r.read_string_array(LengthPrefix::UInt16).map(drop),
+    |r| r.read_map(LengthPrefix::Int32, |r| r.read_string(), |r| r.read_i32()).map(drop),
+    |r| r.read_array_of::<(u8, char)>(LengthPrefix::SevenBitEncodedInt).map(drop),
+    |r| r.read_length_prefix(LengthPrefix::UInt32, usize::MAX).map(drop),
+    |r| r.read_delta_i32_vec(4).map(drop),
+    |r| r.read_frame().map(drop),
+    |r| r.read_chunk_header().map(drop),
+    |r| r.read_flags_enum::<i32>(0xFF).map(drop),
+    |r| r.read_optional(|r| r.read_string()).map(drop),
+    |r| r.read_color(ColorLayout::Bytes).map(drop),
+    |r| r.read_complex().map(drop),
+    |r| r.read_time_only().map(drop),
+    |r| r.read_date_only().map(drop),
+    |r| r.read_oa_date().map(drop),
+    |r| r.read_decimal().map(drop),
+    |r| r.read_guid().map(drop),
+    |r| r.read_guid_string().map(drop),
+    |r| r.read_snorm16().map(drop),
+    |r| r.read_dynamic().map(drop),
+    |r| r.peek_type_code().and_then(|type_code| r.read_raw_for_tag(type_code)).map(drop),
+    |r| r.read_record(&FUZZ_SCHEMA).map(drop),
+    |r| {
+        let mut bits = BitReader::new(r);
+        bits.read_bits(13).map(drop)
+    },
+    #[cfg(feature = "deflate")]
+    |r| r.read_compressed_section().map(drop),
+];
+
+const FUZZ_SCHEMA: [FieldType; 5] = [FieldType::I32, FieldType::String, FieldType::Char, FieldType::Bytes(3), FieldType::SevenBitEncodedInt];
+
+/// Parses `data` with many of [BinaryReader]'s read methods, for fuzzing them with arbitrary
+/// input. Returns the error each read ended with, if any.
+///
+/// Each read method is called repeatedly on a new reader until the data runs out or it fails,
+/// and string reads are repeated under every [Encoding]. Reading never panics, whatever the
+/// input, and no read requests more than `data.len()` bytes, so a fuzzer only has to check that
+/// this returns.
+/// ```
+/// # use csharp_binary_encoding::testutil::try_parse_all;
+/// let errors = try_parse_all(&[0x80, 0x80, 0x80, 0x80, 0x80, 0xFF, 0x00]);
+/// assert!(!errors.is_empty());
+/// ```
+pub fn try_parse_all(data: &[u8]) -> Vec<DataDecodeError> {
+    let mut errors = Vec::new();
+    for encoding in [Encoding::Utf8, Encoding::Utf16, Encoding::Utf16BigEndian, Encoding::Utf16LittleEndian, Encoding::Latin1] {
+        for step in PARSE_STEPS {
+            let mut reader = BinaryReader::new(data);
+            reader.set_max_read_length(data.len());
+            reader.set_strict_floats(true);
+            reader.set_encoding(encoding);
+            loop {
+                match reader.is_at_eof() {
+                    Ok(false) => {},
+                    Ok(true) => break,
+                    Err(error) => {
+                        errors.push(error);
+                        break;
+                    },
+                }
+                let num_bytes_read = reader.num_bytes_read();
+                if let Err(error) = step(&mut reader) {
+                    errors.push(error);
+                    break;
+                }
+                if reader.num_bytes_read() == num_bytes_read {
+                    break;
+                }
+            }
+        }
+    }
+    errors
+}
@@ -0,0 +1,112 @@
+use std::io::Read;
+use super::binaryreader::{BinaryReader, DataDecodeError, InvalidDataError};
+use super::byteorder::{BigEndian, ByteOrder, LittleEndian};
+use super::framing::LengthPrefix;
+
+/// The text encoding used for chars and strings, matching the `Encoding` passed to the C#
+/// BinaryReader and BinaryWriter constructors.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum Encoding {
+    /// UTF-8, the encoding C# uses when none is specified (`Encoding.UTF8`).
+    #[default]
+    Utf8,
+    /// UTF-16 with code units in the byte order of the reader or writer, so with the default
+    /// [LittleEndian](crate::LittleEndian) byte order this is `Encoding.Unicode`. Chars outside
+    /// the basic multilingual plane are written as a surrogate pair of two code units.
+    Utf16,
+    /// UTF-16 with big-endian code units (`Encoding.BigEndianUnicode`), regardless of the byte
+    /// order of the reader or writer.
+    Utf16BigEndian,
+    /// UTF-16 with little-endian code units (`Encoding.Unicode`), regardless of the byte order
+    /// of the reader or writer.
+    Utf16LittleEndian,
+    /// ISO-8859-1, which maps each byte to the char with the same value (`Encoding.Latin1`).
+    /// Like C#, chars above U+00FF are written as `?`.
+    Latin1,
+}
+
+impl Encoding {
+    /// Encodes `data` in this encoding, with [Encoding::Utf16] code units in the byte order `B`.
+    /// Under [Encoding::Latin1] chars above U+00FF are encoded as `?`, like C#.
+    pub(crate) fn encode<B: ByteOrder>(self, data: &str) -> Vec<u8> {
+        match self {
+            Self::Utf8 => data.as_bytes().to_vec(),
+            Self::Utf16 => data.encode_utf16().flat_map(B::to_bytes).collect(),
+            Self::Utf16BigEndian => data.encode_utf16().flat_map(BigEndian::to_bytes).collect(),
+            Self::Utf16LittleEndian => data.encode_utf16().flat_map(LittleEndian::to_bytes).collect(),
+            Self::Latin1 => data.chars().map(|c| u8::try_from(c).unwrap_or(b'?')).collect(),
+        }
+    }
+
+    /// Decodes `bytes` in this encoding, with [Encoding::Utf16] code units in the byte order `B`,
+    /// returning [InvalidDataError::InvalidUtf8] or [InvalidDataError::InvalidUtf16] if they
+    /// aren't valid. Every byte is valid Latin1.
+    pub(crate) fn decode<B: ByteOrder>(self, bytes: Vec<u8>) -> Result<String, InvalidDataError> {
+        match self {
+            Self::Utf8 => String::from_utf8(bytes).map_err(|_| InvalidDataError::InvalidUtf8),
+            Self::Utf16 => decode_utf16_strict(&bytes, B::from_bytes),
+            Self::Utf16BigEndian => decode_utf16_strict(&bytes, BigEndian::from_bytes),
+            Self::Utf16LittleEndian => decode_utf16_strict(&bytes, LittleEndian::from_bytes),
+            Self::Latin1 => Ok(bytes.into_iter().map(char::from).collect()),
+        }
+    }
+}
+
+fn decode_utf16_strict(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Result<String, InvalidDataError> {
+    let (pairs, remainder) = bytes.as_chunks();
+    if !remainder.is_empty() {
+        return Err(InvalidDataError::InvalidUtf16);
+    }
+    char::decode_utf16(pairs.iter().map(|&pair| from_bytes(pair)))
+        .collect::<Result<String, _>>()
+        .map_err(|_| InvalidDataError::InvalidUtf16)
+}
+
+const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: [u8; 2] = [0xFF, 0xFE];
+const UTF16_BE_BOM: [u8; 2] = [0xFE, 0xFF];
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let (pairs, remainder) = bytes.as_chunks();
+    let mut output = String::from_utf16_lossy(&pairs.iter().map(|&pair| from_bytes(pair)).collect::<Vec<u16>>());
+    if !remainder.is_empty() {
+        output.push(char::REPLACEMENT_CHARACTER);
+    }
+    output
+}
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads a number of bytes encoded as specified
+    /// by `prefix`, followed by that many bytes of text in an unknown encoding, and guesses the
+    /// encoding. A byte order mark selects UTF-8, [Encoding::Utf16LittleEndian] or
+    /// [Encoding::Utf16BigEndian] and is removed from the string,
+    /// otherwise the bytes are decoded as UTF-8 if they are valid UTF-8 and as [Encoding::Latin1]
+    /// if they aren't. Invalid data after a byte order mark is replaced with U+FFFD.
+    ///
+    /// This is a best-effort heuristic meant for diagnostic tools, and shouldn't be used to read
+    /// formats with a known encoding.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::NegativeLength]) if the length is
+    /// negative, and [DataDecodeError]::InvalidData([InvalidDataError::LengthLimitExceeded]) if it
+    /// is larger than [BinaryReader::max_read_length].
+    ///
+    pub fn read_string_detect(&mut self, prefix: LengthPrefix) -> Result<(String, Encoding), DataDecodeError> {
+        let length = self.read_length_prefix(prefix, self.max_read_length())?;
+        let bytes = self.read_bytes(length)?;
+        Ok(if let Some(rest) = bytes.strip_prefix(&UTF8_BOM) {
+            (String::from_utf8_lossy(rest).into_owned(), Encoding::Utf8)
+        } else if let Some(rest) = bytes.strip_prefix(&UTF16_LE_BOM) {
+            (decode_utf16(rest, u16::from_le_bytes), Encoding::Utf16LittleEndian)
+        } else if let Some(rest) = bytes.strip_prefix(&UTF16_BE_BOM) {
+            (decode_utf16(rest, u16::from_be_bytes), Encoding::Utf16BigEndian)
+        } else {
+            match String::from_utf8(bytes) {
+                Ok(string) => (string, Encoding::Utf8),
+                Err(e) => (e.into_bytes().into_iter().map(char::from).collect(), Encoding::Latin1),
+            }
+        })
+    }
+}
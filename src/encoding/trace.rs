@@ -0,0 +1,39 @@
+/// Evaluates `$read`, a read from `$reader`, and with the `tracing` feature emits a trace event
+/// with the offset the value was read from and either the value or the error. Without the feature
+/// this is just `$read`.
+macro_rules! trace_read {
+    ($reader:ident, $type:expr, $read:expr) => {{
+        #[cfg(feature = "tracing")]
+        let offset = $reader.num_bytes_read();
+        let result = $read;
+        #[cfg(feature = "tracing")]
+        {
+            match &result {
+                Ok(value) => tracing::trace!(offset, value = ?value, "read {}", $type),
+                Err(error) => tracing::trace!(offset, %error, "failed to read {}", $type),
+            }
+        }
+        result
+    }};
+}
+
+/// Evaluates `$write`, which writes `$value` to `$writer`, and with the `tracing` feature emits a
+/// trace event with the offset the value was written at, the value, and the number of bytes
+/// written or the error. Without the feature this is just `$write`.
+macro_rules! trace_write {
+    ($writer:ident, $type:expr, $value:expr, $write:expr) => {{
+        #[cfg(feature = "tracing")]
+        let offset = $writer.num_bytes_written();
+        let result = $write;
+        #[cfg(feature = "tracing")]
+        {
+            match &result {
+                Ok(len) => tracing::trace!(offset, value = ?$value, len, "wrote {}", $type),
+                Err(error) => tracing::trace!(offset, value = ?$value, %error, "failed to write {}", $type),
+            }
+        }
+        result
+    }};
+}
+
+pub(crate) use {trace_read, trace_write};
@@ -0,0 +1,38 @@
+use std::io::{self, Read, Write};
+use super::binaryreader::{BinaryReader, DataDecodeError, InvalidDataError};
+use super::binarywriter::BinaryWriter;
+use super::byteorder::ByteOrder;
+
+impl<T, B> BinaryReader<T, B>
+where T: Read, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Reads a string as read by
+    /// [BinaryReader::read_string] and parses it as an absolute URI, as C# code commonly stores a
+    /// `Uri` by writing its string form.
+    ///
+    /// Requires the `url` feature.
+    ///
+    /// # Errors
+    /// Returns [DataDecodeError]::InvalidData([InvalidDataError::InvalidUri]) if the string isn't
+    /// a valid absolute URI, along with the errors of [BinaryReader::read_string]. The string is
+    /// consumed even if it is invalid.
+    #[cfg_attr(docsrs, doc(cfg(feature = "url")))]
+    pub fn read_uri(&mut self) -> Result<url::Url, DataDecodeError> {
+        let string = self.read_string()?;
+        url::Url::parse(&string).map_err(|_| InvalidDataError::InvalidUri.into())
+    }
+}
+
+impl<T, B> BinaryWriter<T, B>
+where T: Write, B: ByteOrder {
+
+    /// Doesn't correspond to any specific c# method. Writes the string form of `uri` as written
+    /// by [BinaryWriter::write_string], to be read back by
+    /// [BinaryReader::read_uri](crate::BinaryReader::read_uri).
+    ///
+    /// Requires the `url` feature.
+    #[cfg_attr(docsrs, doc(cfg(feature = "url")))]
+    pub fn write_uri(&mut self, uri: &url::Url) -> io::Result<usize> {
+        self.write_string(uri.as_str())
+    }
+}
@@ -0,0 +1,71 @@
+use std::io::Read;
+use std::ops::{Deref, DerefMut};
+use super::binaryreader::{BinaryReader, DataDecodeError};
+use super::byteorder::ByteOrder;
+
+/// Wraps a [BinaryReader] with the version number of the format being read, for formats which
+/// write a version first and then include or omit fields depending on it.
+///
+/// The wrapped reader can be used directly through [Deref], and fields added in later versions
+/// are read with [VersionedReader::if_version_gte]:
+/// ```
+/// # use csharp_binary_encoding::{BinaryReader, DataDecodeError, VersionedReader};
+/// let data: [u8; 6] = [0x02, 0x00, 0x00, 0x00, 0x2A, 0x07];
+/// let mut reader = BinaryReader::new(data.as_slice());
+/// let mut reader = VersionedReader::read_version(&mut reader)?;
+/// let id = reader.read_byte()?;
+/// // added in version 2
+/// let flags = reader.if_version_gte(2, |r| r.read_byte())?;
+/// assert_eq!((42, Some(7)), (id, flags));
+/// # Ok::<(), DataDecodeError>(())
+/// ```
+#[derive(Debug)]
+pub struct VersionedReader<'a, T: Read, B: ByteOrder> {
+    reader: &'a mut BinaryReader<T, B>,
+    version: u32,
+}
+
+impl<'a, T: Read, B: ByteOrder> VersionedReader<'a, T, B> {
+    /// Creates a VersionedReader which reads from `reader`, for data of the format version
+    /// `version`.
+    pub fn new(reader: &'a mut BinaryReader<T, B>, version: u32) -> Self {
+        Self { reader, version }
+    }
+
+    /// Reads the format version as a UInt32, then creates a VersionedReader for the rest of the
+    /// data.
+    pub fn read_version(reader: &'a mut BinaryReader<T, B>) -> Result<Self, DataDecodeError> {
+        let version = reader.read_u32()?;
+        Ok(Self::new(reader, version))
+    }
+
+    /// Returns the format version of the data being read.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Calls `read_value` and returns what it read if the format version is at least
+    /// `min_version`, or returns `None` without reading anything if it is older.
+    pub fn if_version_gte<R, F>(&mut self, min_version: u32, read_value: F) -> Result<Option<R>, DataDecodeError>
+    where F: FnOnce(&mut BinaryReader<T, B>) -> Result<R, DataDecodeError> {
+        if self.version >= min_version {
+            read_value(self.reader).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T: Read, B: ByteOrder> Deref for VersionedReader<'_, T, B> {
+    type Target = BinaryReader<T, B>;
+
+    fn deref(&self) -> &Self::Target {
+        self.reader
+    }
+}
+
+impl<T: Read, B: ByteOrder> DerefMut for VersionedReader<'_, T, B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.reader
+    }
+}
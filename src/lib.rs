@@ -3,13 +3,76 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 mod encoding {
+    mod trace;
     mod binaryreader;
     pub use binaryreader::{BinaryReader, DataDecodeError, InvalidDataError};
     mod binarywriter;
-    pub use binarywriter::BinaryWriter;
+    pub use binarywriter::{BinaryWriter, ReserveCapacity};
+    mod csharpreadable;
+    pub use csharpreadable::CsharpReadable;
+    mod csharpwritable;
+    pub use csharpwritable::CsharpWritable;
+    mod framing;
+    pub use framing::{Frames, LengthPrefix, NullStringEncoding, TagEncoding};
+    mod textencoding;
+    pub use textencoding::Encoding;
+    mod byteorder;
+    pub use byteorder::{BigEndian, ByteOrder, LittleEndian, Primitive};
+    mod color;
+    pub use color::{Color, ColorLayout};
+    pub mod bytes;
+    mod dynamic;
+    pub use dynamic::{DynamicValue, TypeCode};
+    mod schema;
+    pub use schema::{FieldType, FieldValue, RecordPlan};
+    mod datetime;
+    pub use datetime::{DateOnly, DateTime, TimeOnly};
+    mod decimal;
+    pub use decimal::Decimal;
+    #[cfg(feature = "deflate")]
+    mod compression;
+    #[cfg(feature = "url")]
+    mod uri;
+    #[cfg(feature = "serde_json")]
+    mod json;
+    mod bits;
+    pub use bits::{BitOrder, BitReader, BitWriter};
+    mod normalized;
+    mod complex;
+    pub use complex::Complex;
+    mod guid;
+    pub use guid::Guid;
+    mod ipaddress;
+    mod versioned;
+    pub use versioned::VersionedReader;
+    mod arraywriter;
+    pub use arraywriter::ArrayWriter;
+    mod bufferedseekreader;
+    pub use bufferedseekreader::BufferedSeekReader;
+    #[cfg_attr(docsrs, doc(cfg(feature = "testutil")))]
+    #[cfg(any(test, feature = "testutil"))]
+    pub mod testutil;
 }
 pub use encoding::{BinaryReader, DataDecodeError, InvalidDataError};
-pub use encoding::BinaryWriter;
+pub use encoding::{BinaryWriter, ReserveCapacity};
+pub use encoding::{CsharpReadable, CsharpWritable};
+pub use encoding::{Frames, LengthPrefix, NullStringEncoding, TagEncoding};
+pub use encoding::Encoding;
+pub use encoding::{BigEndian, ByteOrder, LittleEndian, Primitive};
+pub use encoding::{Color, ColorLayout};
+pub use encoding::bytes;
+pub use encoding::{DynamicValue, TypeCode};
+pub use encoding::{FieldType, FieldValue, RecordPlan};
+pub use encoding::{DateOnly, DateTime, TimeOnly};
+pub use encoding::Decimal;
+pub use encoding::{BitOrder, BitReader, BitWriter};
+pub use encoding::Complex;
+pub use encoding::Guid;
+pub use encoding::VersionedReader;
+pub use encoding::ArrayWriter;
+pub use encoding::BufferedSeekReader;
+#[cfg(any(test, feature = "testutil"))]
+pub use encoding::testutil;
 
 
 #[cfg(test)]
@@ -24,7 +87,7 @@ mod tests {
         use std::fs::File;
         use xshell::{Shell, cmd};
         let sh = Shell::new().unwrap();
-        sh.change_dir(&TEST_FOLDER.to_string());
+        sh.change_dir(TEST_FOLDER);
         cmd!(sh, "dotnet run generate").run().unwrap();
 
         let file = File::open(TEST_FOLDER.to_string()+ "/output.bin")?;
@@ -35,7 +98,7 @@ mod tests {
         assert!(!reader.read_boolean()?);
         assert_eq!(0x45, reader.read_byte()?);
         assert_eq!(vec![0x01, 0x02, 0x03, 0x04, 0x05], reader.read_bytes(5)?);
-        assert_eq!('\u{2603}' as char, reader.read_char()?);
+        assert_eq!('\u{2603}', reader.read_char()?);
         assert_eq!(727.247_f64, reader.read_f64()?);
         cfg_if::cfg_if! {
             if #[cfg(feature = "f16")] {
@@ -58,6 +121,14 @@ mod tests {
         assert_eq!(3000000000_u32, reader.read_u32()?);
         assert_eq!(42307830165_u64, reader.read_u64()?);
         assert_eq!('\0', reader.read_char()?);
+        assert_eq!(TimeOnly::from_ticks(490_200_000_000)?, reader.read_time_only()?);
+        assert_eq!(DateOnly::from_day_number(739_341)?, reader.read_date_only()?);
+        assert_eq!(Decimal::new(-727247, 3)?, reader.read_decimal()?);
+        let guid: Guid = "0f8fad5b-d9cb-469f-a165-70867728950e".parse()?;
+        assert_eq!(guid, reader.read_guid()?);
+        assert_eq!(guid, reader.read_guid_string()?);
+        assert!(reader.read_byte_array()?.is_empty());
+        assert_eq!(vec![0xDE, 0xAD, 0xBE, 0xEF], reader.read_byte_array()?);
 
         let _ = cmd!(sh, "rm -f output.bin").run();
 
@@ -70,9 +141,9 @@ mod tests {
         use xshell::{Shell, cmd};
         
         let sh = Shell::new().unwrap();
-        sh.change_dir(&TEST_FOLDER.to_string());
+        sh.change_dir(TEST_FOLDER);
 
-        let _ = cmd!(sh, "rm -f input.bin").run().unwrap();
+        cmd!(sh, "rm -f input.bin").run().unwrap();
 
         let mut file = File::create(TEST_FOLDER.to_string() + "/input.bin").unwrap();
         let mut writer = BinaryWriter::new(&mut file);
@@ -104,6 +175,14 @@ mod tests {
         writer.write_u32(3000000000).unwrap();
         writer.write_u64(42307830165).unwrap();
         writer.write_char('\0').unwrap();
+        writer.write_time_only(TimeOnly::from_ticks(490_200_000_000).unwrap()).unwrap();
+        writer.write_date_only(DateOnly::from_day_number(739_341).unwrap()).unwrap();
+        writer.write_decimal(Decimal::new(-727247, 3).unwrap()).unwrap();
+        let guid: Guid = "0f8fad5b-d9cb-469f-a165-70867728950e".parse().unwrap();
+        writer.write_guid(guid).unwrap();
+        writer.write_guid_string(guid).unwrap();
+        writer.write_byte_array(&[]).unwrap();
+        writer.write_byte_array(&[0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
 
         cfg_if::cfg_if!{
             if #[cfg(feature = "f16")] {
@@ -132,4 +211,2397 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn round_trip_tuple() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_i32(-42)?;
+        writer.write_string("meow")?;
+        writer.write_f64(7.27)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let tuple: (i32, String, f64) = reader.read_tuple3()?;
+        assert_eq!((-42, "meow".to_string(), 7.27), tuple);
+        Ok(())
+    }
+
+    #[test]
+    fn reserve_capacity() -> Result<(), std::io::Error> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.reserve(64);
+        writer.write_i32(-100)?;
+        writer.write_string("meow")?;
+
+        assert!(data.capacity() >= 64);
+        assert_eq!(vec![0x9C, 0xFF, 0xFF, 0xFF, 0x04, 0x6D, 0x65, 0x6F, 0x77], data);
+
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        BinaryWriter::new(&mut cursor).reserve(32);
+        assert!(cursor.get_ref().capacity() >= 32);
+        Ok(())
+    }
+
+    #[test]
+    fn read_bytes_length_limit() {
+        let data: [u8; 4] = [0x01, 0x02, 0x03, 0x04];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(matches!(
+            reader.read_bytes(usize::MAX),
+            Err(DataDecodeError::InvalidData(InvalidDataError::LengthLimitExceeded))
+        ));
+
+        reader.set_max_read_length(2);
+        assert!(matches!(
+            reader.read_bytes(3),
+            Err(DataDecodeError::InvalidData(InvalidDataError::LengthLimitExceeded))
+        ));
+        assert_eq!(vec![0x01, 0x02], reader.read_bytes(2).unwrap());
+        assert_eq!(2, reader.num_bytes_read());
+    }
+
+    #[test]
+    fn read_boolean_raw() -> Result<(), DataDecodeError> {
+        let data: [u8; 2] = [0x00, 0x42];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!((false, 0x00), reader.read_boolean_raw()?);
+        assert_eq!((true, 0x42), reader.read_boolean_raw()?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_tagged() -> Result<(), DataDecodeError> {
+        #[derive(Debug, PartialEq)]
+        enum Shape {
+            Circle(f32),
+            Rectangle(i32, i32),
+        }
+
+        fn write_shape<W: std::io::Write>(writer: &mut BinaryWriter<W>, shape: &Shape, encoding: TagEncoding) -> std::io::Result<usize> {
+            match shape {
+                Shape::Circle(radius) => writer.write_tagged(encoding, 1, |w| w.write_f32(*radius)),
+                Shape::Rectangle(width, height) => writer.write_tagged(encoding, 2, |w| {
+                    w.write_i32(*width)?;
+                    w.write_i32(*height)
+                }),
+            }
+        }
+
+        fn read_shape<R: std::io::Read>(reader: &mut BinaryReader<R>, encoding: TagEncoding) -> Result<Shape, DataDecodeError> {
+            reader.read_tagged(encoding, |tag, r| match tag {
+                1 => Ok(Shape::Circle(r.read_f32()?)),
+                2 => Ok(Shape::Rectangle(r.read_i32()?, r.read_i32()?)),
+                _ => panic!("unexpected tag {tag}"),
+            })
+        }
+
+        for encoding in [TagEncoding::Byte, TagEncoding::Int32, TagEncoding::SevenBitEncodedInt] {
+            let shapes = [Shape::Circle(1.5), Shape::Rectangle(3, -4)];
+            let mut data: Vec<u8> = Vec::new();
+            let mut writer = BinaryWriter::new(&mut data);
+            for shape in &shapes {
+                write_shape(&mut writer, shape, encoding)?;
+            }
+
+            let mut reader = BinaryReader::new(data.as_slice());
+            for shape in &shapes {
+                assert_eq!(shape, &read_shape(&mut reader, encoding)?);
+            }
+        }
+
+        let mut writer = BinaryWriter::new(Vec::new());
+        assert!(writer.write_tagged(TagEncoding::Byte, 256, |w| w.write_byte(0)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn read_bytes_buffered() -> Result<(), DataDecodeError> {
+        use std::io::BufReader;
+        let data: [u8; 8] = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+        // a tiny capacity forces reads to span several fills of the BufReader
+        let mut reader = BinaryReader::new(BufReader::with_capacity(3, data.as_slice()));
+
+        assert_eq!(0x01, reader.peek_byte()?);
+        assert_eq!(vec![0x01, 0x02, 0x03, 0x04, 0x05], reader.read_bytes_buffered(5)?);
+        assert!(matches!(
+            reader.read_bytes_buffered(4),
+            Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))
+        ));
+        assert_eq!(vec![0x06, 0x07, 0x08], reader.read_bytes_buffered(3)?);
+        assert_eq!(8, reader.num_bytes_read());
+        Ok(())
+    }
+
+    #[test]
+    fn write_char_utf16() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.set_encoding(Encoding::Utf16);
+        assert_eq!(2, writer.write_char('\u{2603}')?);
+        assert_eq!(4, writer.write_char('\u{1D11E}')?);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(0x2603, reader.read_u16()?);
+        assert_eq!(0xD834, reader.read_u16()?);
+        assert_eq!(0xDD1E, reader.read_u16()?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_byte_orders() -> Result<(), DataDecodeError> {
+        let mut big: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::<_, BigEndian>::with_byte_order(&mut big);
+        writer.write_i32(1)?;
+        writer.write_u16(0x1234)?;
+        writer.write_f64(7.27)?;
+        assert_eq!(vec![0x00, 0x00, 0x00, 0x01, 0x12, 0x34], big[0..6]);
+
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order(big.as_slice());
+        assert_eq!(1, reader.read_i32()?);
+        assert_eq!(0x1234, reader.read_u16()?);
+        assert_eq!(7.27, reader.read_f64()?);
+
+        let mut little: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::<_, LittleEndian>::with_byte_order(&mut little);
+        writer.write_i32(1)?;
+        writer.write_u16(0x1234)?;
+        writer.write_f64(7.27)?;
+        assert_eq!(vec![0x01, 0x00, 0x00, 0x00, 0x34, 0x12], little[0..6]);
+
+        let mut reader = BinaryReader::new(little.as_slice());
+        assert_eq!(1, reader.read_i32()?);
+        assert_eq!(0x1234, reader.read_u16()?);
+        assert_eq!(7.27, reader.read_f64()?);
+
+        // reading with the wrong byte order gives a different value
+        let mut reader = BinaryReader::new(big.as_slice());
+        assert_eq!(0x01000000, reader.read_i32()?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_color() -> Result<(), DataDecodeError> {
+        let color = Color { a: 0xFF, r: 0x12, g: 0x34, b: 0x56 };
+        assert_eq!(0xFF123456, color.to_argb());
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_color(color, ColorLayout::PackedArgb)?;
+        writer.write_color(color, ColorLayout::Bytes)?;
+        assert_eq!(vec![0x56, 0x34, 0x12, 0xFF, 0xFF, 0x12, 0x34, 0x56], data);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(color, reader.read_color(ColorLayout::PackedArgb)?);
+        assert_eq!(color, reader.read_color(ColorLayout::Bytes)?);
+
+        let mut big: Vec<u8> = Vec::new();
+        BinaryWriter::<_, BigEndian>::with_byte_order(&mut big).write_color(color, ColorLayout::PackedArgb)?;
+        assert_eq!(vec![0xFF, 0x12, 0x34, 0x56], big);
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order(big.as_slice());
+        assert_eq!(color, reader.read_color(ColorLayout::PackedArgb)?);
+        Ok(())
+    }
+
+    #[test]
+    fn expect_magic() -> Result<(), DataDecodeError> {
+        let data = b"RIFFWAVE";
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.expect_magic(b"RIFF")?;
+        assert!(matches!(
+            reader.expect_magic(b"AVI "),
+            Err(DataDecodeError::InvalidData(InvalidDataError::BadMagic))
+        ));
+        assert_eq!(8, reader.num_bytes_read());
+        Ok(())
+    }
+
+    #[test]
+    fn read_string_incremental_utf8() -> Result<(), DataDecodeError> {
+        // a valid string long enough to be validated in several chunks, with multi-byte
+        // characters straddling the chunk boundaries
+        let long_string = "\u{2603}meow".repeat(2000);
+        let mut data: Vec<u8> = Vec::new();
+        BinaryWriter::new(&mut data).write_string(&long_string)?;
+        assert_eq!(long_string, BinaryReader::new(data.as_slice()).read_string()?);
+
+        // declares a million bytes, but the data is invalid right away
+        let mut data: Vec<u8> = Vec::new();
+        BinaryWriter::new(&mut data).write_7_bit_encoded_int(1_000_000)?;
+        data.extend_from_slice(&[0x6D, 0xFF, 0x6F, 0x77]);
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(matches!(
+            reader.read_string(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::InvalidUtf8))
+        ));
+
+        // a character cut off at the end of the string is invalid
+        let data: [u8; 3] = [0x02, 0xE2, 0x98];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(matches!(
+            reader.read_string(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::InvalidUtf8))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_map() -> Result<(), DataDecodeError> {
+        let dictionary = vec![(1, "one".to_string()), (2, "two".to_string()), (3, "three".to_string())];
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_map(
+            LengthPrefix::Int32,
+            dictionary.iter().map(|(key, value)| (key, value)),
+            |w, key| w.write_i32(*key),
+            |w, value| w.write_string(value),
+        )?;
+        assert_eq!([0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x03], data[0..9]);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let pairs = reader.read_map(LengthPrefix::Int32, |r| r.read_i32(), |r| r.read_string())?;
+        assert_eq!(dictionary, pairs);
+
+        let data: [u8; 4] = (-1_i32).to_le_bytes();
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(matches!(
+            reader.read_map(LengthPrefix::Int32, |r| r.read_i32(), |r| r.read_i32()),
+            Err(DataDecodeError::InvalidData(InvalidDataError::NegativeLength))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn read_budget() -> Result<(), DataDecodeError> {
+        let data: [u8; 8] = [0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00];
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.set_max_total_bytes(Some(6));
+        assert_eq!(1, reader.read_i32()?);
+        assert!(matches!(
+            reader.read_i32(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::LengthLimitExceeded))
+        ));
+        assert_eq!(0x02, reader.read_u16()?);
+        assert!(matches!(
+            reader.read_byte(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::LengthLimitExceeded))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn slice_functions() {
+        macro_rules! check {
+            ($value:expr, $size:expr, $read_le:ident, $read_be:ident, $write_le:ident, $write_be:ident) => {
+                let mut data = [0_u8; $size + 1];
+                let rest = bytes::$write_le(&mut data, $value).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!($value.to_le_bytes(), data[0..$size]);
+                assert_eq!(($value, [0_u8].as_slice()), bytes::$read_le(&data).unwrap());
+
+                bytes::$write_be(&mut data, $value).unwrap();
+                assert_eq!($value.to_be_bytes(), data[0..$size]);
+                assert_eq!(($value, [0_u8].as_slice()), bytes::$read_be(&data).unwrap());
+
+                let short = &mut data[0..$size - 1];
+                assert_eq!(Err(InvalidDataError::NotEnoughBytes), bytes::$read_le(short));
+                assert_eq!(Err(InvalidDataError::NotEnoughBytes), bytes::$read_be(short));
+                assert_eq!(Err(InvalidDataError::NotEnoughBytes), bytes::$write_le(short, $value));
+                assert_eq!(Err(InvalidDataError::NotEnoughBytes), bytes::$write_be(short, $value));
+            };
+        }
+        check!(-5_i16, 2, read_i16_le, read_i16_be, write_i16_le, write_i16_be);
+        check!(-100_i32, 4, read_i32_le, read_i32_be, write_i32_le, write_i32_be);
+        check!(-2147483649_i64, 8, read_i64_le, read_i64_be, write_i64_le, write_i64_be);
+        check!(624_u16, 2, read_u16_le, read_u16_be, write_u16_le, write_u16_be);
+        check!(3000000000_u32, 4, read_u32_le, read_u32_be, write_u32_le, write_u32_be);
+        check!(42307830165_u64, 8, read_u64_le, read_u64_be, write_u64_le, write_u64_be);
+        check!(5.2_f32, 4, read_f32_le, read_f32_be, write_f32_le, write_f32_be);
+        check!(727.247_f64, 8, read_f64_le, read_f64_be, write_f64_le, write_f64_be);
+    }
+
+    #[test]
+    fn round_trip_cstring() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(5, writer.write_cstring("meow")?);
+        assert_eq!(1, writer.write_cstring("")?);
+        writer.write_cstring("\u{2603}")?;
+        assert!(writer.write_cstring("me\0ow").is_err());
+        assert_eq!(b"meow\0\0\xE2\x98\x83\0".as_slice(), data.as_slice());
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!("meow", reader.read_cstring()?);
+        assert_eq!("", reader.read_cstring()?);
+        assert_eq!("\u{2603}", reader.read_cstring()?);
+
+        let mut reader = BinaryReader::new(b"meow".as_slice());
+        assert!(matches!(
+            reader.read_cstring(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_flags() -> Result<(), DataDecodeError> {
+        let flags = [true, false, true, true, false, false, false, true];
+        let mut data: Vec<u8> = Vec::new();
+        BinaryWriter::new(&mut data).write_flags(flags)?;
+        assert_eq!(vec![0b10001101], data);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(flags, reader.read_flags()?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_dynamic() -> Result<(), DataDecodeError> {
+        let values = [
+            DynamicValue::Int32(-723),
+            DynamicValue::String("meow".to_string()),
+            DynamicValue::Boolean(true),
+        ];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        for value in &values {
+            writer.write_dynamic(value)?;
+        }
+        assert_eq!(vec![9, 0x2D, 0xFD, 0xFF, 0xFF, 18, 4, b'm', b'e', b'o', b'w', 3, 1], data);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        for value in &values {
+            assert_eq!(value, &reader.read_dynamic()?);
+        }
+
+        let mut reader = BinaryReader::new([0x11, 0x00].as_slice());
+        assert!(matches!(
+            reader.read_dynamic(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::UnknownTypeCode))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn unread() -> Result<(), DataDecodeError> {
+        let data: [u8; 3] = [0x2C, 0x01, 0x07];
+        let mut reader = BinaryReader::new(data.as_slice());
+        let value = reader.read_i16()?;
+        assert_eq!(300, value);
+        reader.unread(&value.to_le_bytes());
+        assert_eq!(0, reader.num_bytes_read());
+        assert_eq!(300, reader.read_i16()?);
+        assert_eq!(0x07, reader.read_byte()?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_zigzag_varint() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(1, writer.write_zigzag_varint32(-1)?);
+        assert_eq!(1, writer.write_zigzag_varint32(-64)?);
+        assert_eq!(2, writer.write_zigzag_varint32(-65)?);
+        assert_eq!(5, writer.write_zigzag_varint32(i32::MIN)?);
+        assert_eq!(1, writer.write_zigzag_varint64(-2)?);
+        assert_eq!(10, writer.write_zigzag_varint64(i64::MAX)?);
+        // the C# encoding of -1 needs the full 5 bytes
+        assert_eq!(5, writer.write_7_bit_encoded_int(-1)?);
+        assert_eq!(vec![0x01, 0x7F, 0x81, 0x01], data[0..4]);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(-1, reader.read_zigzag_varint32()?);
+        assert_eq!(-64, reader.read_zigzag_varint32()?);
+        assert_eq!(-65, reader.read_zigzag_varint32()?);
+        assert_eq!(i32::MIN, reader.read_zigzag_varint32()?);
+        assert_eq!(-2, reader.read_zigzag_varint64()?);
+        assert_eq!(i64::MAX, reader.read_zigzag_varint64()?);
+        assert_eq!(-1, reader.read_7_bit_encoded_int()?);
+        Ok(())
+    }
+
+    #[test]
+    fn read_record() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_i32(-100)?;
+        writer.write_string("meow")?;
+        writer.write_f64(727.247)?;
+        writer.write_bytes(&[0x01, 0x02, 0x03])?;
+        writer.write_7_bit_encoded_int(404)?;
+
+        let schema = [
+            FieldType::I32,
+            FieldType::String,
+            FieldType::F64,
+            FieldType::Bytes(3),
+            FieldType::SevenBitEncodedInt,
+        ];
+        let mut reader = BinaryReader::new(data.as_slice());
+        let record = reader.read_record(&schema)?;
+        assert_eq!(vec![
+            FieldValue::I32(-100),
+            FieldValue::String("meow".to_string()),
+            FieldValue::F64(727.247),
+            FieldValue::Bytes(vec![0x01, 0x02, 0x03]),
+            FieldValue::SevenBitEncodedInt(404),
+        ], record);
+        assert!(record.iter().map(FieldValue::field_type).eq(schema));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_record() -> Result<(), DataDecodeError> {
+        let record = vec![
+            FieldValue::Boolean(true),
+            FieldValue::U16(624),
+            FieldValue::Char('\u{2603}'),
+            FieldValue::String("meow".to_string()),
+            FieldValue::SevenBitEncodedInt64(-500000000000000000),
+            FieldValue::F32(5.2),
+        ];
+        let mut data: Vec<u8> = Vec::new();
+        let written = BinaryWriter::new(&mut data).write_record(&record)?;
+        assert_eq!(data.len(), written);
+
+        let schema: Vec<FieldType> = record.iter().map(FieldValue::field_type).collect();
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(record, reader.read_record(&schema)?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_time_only_date_only() -> Result<(), DataDecodeError> {
+        let time = TimeOnly::from_ticks(TimeOnly::TICKS_PER_DAY - 1)?;
+        let date = DateOnly::from_day_number(DateOnly::MAX_DAY_NUMBER)?;
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_time_only(time)?;
+        writer.write_date_only(date)?;
+        writer.write_i64(TimeOnly::TICKS_PER_DAY)?;
+        writer.write_i32(-1)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(time, reader.read_time_only()?);
+        assert_eq!(date, reader.read_date_only()?);
+        assert!(matches!(reader.read_time_only(), Err(DataDecodeError::InvalidData(InvalidDataError::OutOfRange))));
+        assert!(matches!(reader.read_date_only(), Err(DataDecodeError::InvalidData(InvalidDataError::OutOfRange))));
+        Ok(())
+    }
+
+    #[test]
+    fn peek_7_bit_encoded_int_len() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_7_bit_encoded_int(100)?;
+        writer.write_7_bit_encoded_int(1 << 20)?;
+        writer.write_7_bit_encoded_int(-1)?;
+        writer.write_bytes(&[0xFF, 0xFF, 0xFF, 0xFF, 0x10])?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        for expected_len in [1, 3, 5] {
+            assert_eq!(expected_len, reader.peek_7_bit_encoded_int_len()?);
+            let before = reader.num_bytes_read();
+            reader.read_7_bit_encoded_int()?;
+            assert_eq!(expected_len as u64, reader.num_bytes_read() - before);
+        }
+        assert!(matches!(reader.peek_7_bit_encoded_int_len(), Err(DataDecodeError::InvalidData(InvalidDataError::IntegerOverflow))));
+        assert_eq!(&[0xFF, 0xFF, 0xFF, 0xFF, 0x10], reader.peek_bytes(5)?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_interned_string() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(22, writer.write_interned_string("meowmeowmeowmeowmeow")?);
+        assert_eq!(6, writer.write_interned_string("meow")?);
+        assert_eq!(1, writer.write_interned_string("meowmeowmeowmeowmeow")?);
+        assert_eq!(1, writer.write_interned_string("meow")?);
+        writer.write_7_bit_encoded_int(3)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!("meowmeowmeowmeowmeow", reader.read_interned_string()?);
+        assert_eq!("meow", reader.read_interned_string()?);
+        assert_eq!("meowmeowmeowmeowmeow", reader.read_interned_string()?);
+        assert_eq!("meow", reader.read_interned_string()?);
+        assert!(matches!(reader.read_interned_string(), Err(DataDecodeError::InvalidData(InvalidDataError::IndexOutOfRange))));
+        Ok(())
+    }
+
+    #[test]
+    fn read_at() -> Result<(), DataDecodeError> {
+        use std::io::{Cursor, Seek, SeekFrom};
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_u16(0xCAFE)?;
+        writer.write_i32(-100)?;
+        writer.write_f64(727.247)?;
+        writer.write_string("meow")?;
+
+        let mut reader = BinaryReader::new(Cursor::new(data));
+        assert_eq!(0xCAFE_u16, reader.read_u16()?);
+        assert_eq!("meow", reader.read_at::<String>(14)?);
+        assert_eq!(727.247_f64, reader.read_at::<f64>(6)?);
+        assert_eq!(2, reader.num_bytes_read());
+        assert_eq!(-100, reader.read_i32()?);
+
+        // seeking relative to the current position accounts for bytes buffered by a peek
+        reader.peek_bytes(8)?;
+        assert_eq!(6, reader.stream_position()?);
+        reader.seek(SeekFrom::Current(-4))?;
+        assert_eq!(-100, reader.read_i32()?);
+        Ok(())
+    }
+
+    #[test]
+    fn read_byte_or_eof() -> Result<(), DataDecodeError> {
+        let data: [u8; 3] = [0x01, 0x02, 0x03];
+        let mut reader = BinaryReader::new(data.as_slice());
+        let mut bytes = Vec::new();
+        while let Some(byte) = reader.read_byte_or_eof()? {
+            bytes.push(byte);
+        }
+        assert_eq!(data.as_slice(), bytes);
+        assert_eq!(None, reader.read_byte_or_eof()?);
+        assert_eq!(3, reader.num_bytes_read());
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_usize_isize() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(8, writer.write_usize_as_u64(usize::MAX)?);
+        assert_eq!(8, writer.write_isize_as_i64(isize::MIN)?);
+        writer.write_u64(u32::MAX as u64 + 1)?;
+        writer.write_i64(i32::MIN as i64 - 1)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(usize::MAX, reader.read_u64_as_usize()?);
+        assert_eq!(isize::MIN, reader.read_i64_as_isize()?);
+        cfg_if::cfg_if! {
+            if #[cfg(target_pointer_width = "32")] {
+                assert!(matches!(reader.read_u64_as_usize(), Err(DataDecodeError::InvalidData(InvalidDataError::IntegerOverflow))));
+                assert!(matches!(reader.read_i64_as_isize(), Err(DataDecodeError::InvalidData(InvalidDataError::IntegerOverflow))));
+            } else {
+                assert_eq!(u32::MAX as usize + 1, reader.read_u64_as_usize()?);
+                assert_eq!(i32::MIN as isize - 1, reader.read_i64_as_isize()?);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn read_dynamic_max_depth() -> Result<(), DataDecodeError> {
+        let mut value = DynamicValue::Object(vec![DynamicValue::Int32(-100), DynamicValue::String("meow".to_string())]);
+        for _ in 0..9 {
+            value = DynamicValue::Object(vec![value, DynamicValue::Empty]);
+        }
+        let mut data: Vec<u8> = Vec::new();
+        BinaryWriter::new(&mut data).write_dynamic(&value)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.set_max_depth(10);
+        assert_eq!(value, reader.read_dynamic()?);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.set_max_depth(9);
+        assert!(matches!(reader.read_dynamic(), Err(DataDecodeError::InvalidData(InvalidDataError::MaxDepthExceeded))));
+
+        // a stream of nothing but nested array headers stops at the default limit
+        let data: Vec<u8> = [TypeCode::Object as u8, 1].repeat(100_000);
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(matches!(reader.read_dynamic(), Err(DataDecodeError::InvalidData(InvalidDataError::MaxDepthExceeded))));
+        Ok(())
+    }
+
+    #[test]
+    fn with_length_suffix() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        let written = writer.with_length_suffix(LengthPrefix::Int32, |writer| {
+            writer.write_string("meow")?;
+            writer.write_f64(727.247)
+        })?;
+        assert_eq!(17, written);
+        writer.with_length_suffix(LengthPrefix::UInt16, |_| Ok(()))?;
+        assert!(writer.with_length_suffix(LengthPrefix::UInt16, |writer| writer.write_bytes(&[0; 65536])).is_err());
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!("meow", reader.read_string()?);
+        assert_eq!(727.247, reader.read_f64()?);
+        assert_eq!(13, reader.read_i32()?);
+        assert_eq!(0, reader.read_u16()?);
+        Ok(())
+    }
+
+    #[test]
+    fn with_length_prefix() -> Result<(), DataDecodeError> {
+        use std::io::{Cursor, Seek};
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = BinaryWriter::<_, BigEndian>::with_byte_order(&mut cursor);
+        writer.write_byte(0x45)?;
+        let written = writer.with_length_prefix(|writer| {
+            writer.write_string("meow")?;
+            writer.write_f64(727.247)
+        })?;
+        assert_eq!(17, written);
+        assert_eq!(18, writer.num_bytes_written());
+        assert_eq!(18, writer.stream_position()?);
+        writer.write_byte(0x46)?;
+
+        let data = cursor.into_inner();
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order(data.as_slice());
+        assert_eq!(0x45, reader.read_byte()?);
+        assert_eq!(13, reader.read_i32()?);
+        assert_eq!("meow", reader.read_string()?);
+        assert_eq!(727.247, reader.read_f64()?);
+        assert_eq!(0x46, reader.read_byte()?);
+        Ok(())
+    }
+
+    #[test]
+    fn leb128_and_7_bit_encoded_negative_values() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(1, writer.write_leb128_i32(-1)?);
+        assert_eq!(5, writer.write_7_bit_encoded_int(-1)?);
+        assert_eq!(2, writer.write_leb128_i32(-129)?);
+        assert_eq!(2, writer.write_leb128_i32(64)?);
+        writer.write_leb128_i32(i32::MIN)?;
+        writer.write_leb128_i32(i32::MAX)?;
+        assert_eq!(10, writer.write_leb128_i64(i64::MIN)?);
+        writer.write_leb128_i64(i64::MAX)?;
+        assert_eq!(&[0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0xFF, 0x7E, 0xC0, 0x00], &data[0..10]);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(-1, reader.read_leb128_i32()?);
+        assert_eq!(-1, reader.read_7_bit_encoded_int()?);
+        assert_eq!(-129, reader.read_leb128_i32()?);
+        assert_eq!(64, reader.read_leb128_i32()?);
+        assert_eq!(i32::MIN, reader.read_leb128_i32()?);
+        assert_eq!(i32::MAX, reader.read_leb128_i32()?);
+        assert_eq!(i64::MIN, reader.read_leb128_i64()?);
+        assert_eq!(i64::MAX, reader.read_leb128_i64()?);
+
+        // the same byte means different values under each convention
+        assert_eq!(127, BinaryReader::new([0x7F].as_slice()).read_7_bit_encoded_int()?);
+        assert_eq!(-1, BinaryReader::new([0x7F].as_slice()).read_leb128_i32()?);
+        // C#'s encoding of -1 is 0xFFFFFFFF when read as signed LEB128, which overflows an i32
+        let mut reader = BinaryReader::new([0xFF, 0xFF, 0xFF, 0xFF, 0x0F].as_slice());
+        assert!(matches!(reader.read_leb128_i32(), Err(DataDecodeError::InvalidData(InvalidDataError::IntegerOverflow))));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_oa_date() -> Result<(), DataDecodeError> {
+        const TICKS_PER_DAY: i64 = TimeOnly::TICKS_PER_DAY;
+        const TICKS_PER_HOUR: i64 = TICKS_PER_DAY / 24;
+        let epoch = DateTime::from_ticks(693_593 * TICKS_PER_DAY)?;
+        // 12:00 on April 1, 2025
+        let noon = DateTime::from_ticks(739_341 * TICKS_PER_DAY + 12 * TICKS_PER_HOUR)?;
+        // 6:00 on December 29, 1899
+        let before_epoch = DateTime::from_ticks(693_592 * TICKS_PER_DAY + 6 * TICKS_PER_HOUR)?;
+        assert_eq!(Some(0.0), epoch.to_oa_date());
+        assert_eq!(Some(45748.5), noon.to_oa_date());
+        assert_eq!(Some(-1.25), before_epoch.to_oa_date());
+        assert_eq!(Some(0.0), DateTime::default().to_oa_date());
+        assert_eq!(None, DateTime::from_ticks(TICKS_PER_DAY)?.to_oa_date());
+        // sub-millisecond parts before the epoch are truncated toward it, like C#
+        assert_eq!(Some(0.0), DateTime::from_ticks(epoch.ticks() - 5000)?.to_oa_date());
+        assert_eq!(Some(-1.25), DateTime::from_ticks(before_epoch.ticks() - 5000)?.to_oa_date());
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_oa_date(epoch)?;
+        writer.write_oa_date(noon)?;
+        writer.write_oa_date(before_epoch)?;
+        assert!(writer.write_oa_date(DateTime::from_ticks(TICKS_PER_DAY)?).is_err());
+        writer.write_f64(f64::NAN)?;
+        writer.write_f64(2_958_466.0)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(epoch, reader.read_oa_date()?);
+        assert_eq!(noon, reader.read_oa_date()?);
+        assert_eq!(before_epoch, reader.read_oa_date()?);
+        assert!(matches!(reader.read_oa_date(), Err(DataDecodeError::InvalidData(InvalidDataError::OutOfRange))));
+        assert!(matches!(reader.read_oa_date(), Err(DataDecodeError::InvalidData(InvalidDataError::OutOfRange))));
+        Ok(())
+    }
+
+    #[test]
+    fn read_raw_for_tag() -> Result<(), DataDecodeError> {
+        let values = [
+            DynamicValue::Int32(-100),
+            DynamicValue::String("meow".to_string()),
+            DynamicValue::Char('\u{2603}'),
+            DynamicValue::Object(vec![DynamicValue::Double(727.247), DynamicValue::Empty]),
+        ];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        for value in &values {
+            writer.write_dynamic(value)?;
+        }
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let mut copied: Vec<u8> = Vec::new();
+        let mut raw_values = Vec::new();
+        for _ in 0..values.len() {
+            let type_code = reader.read_byte()?;
+            let raw = reader.read_raw_for_tag(TypeCode::try_from(type_code)?)?;
+            copied.push(type_code);
+            copied.extend(&raw);
+            raw_values.push(raw);
+        }
+        assert_eq!(data, copied);
+        assert_eq!((-100_i32).to_le_bytes().as_slice(), raw_values[0]);
+        assert_eq!(b"\x04meow".as_slice(), raw_values[1]);
+        assert_eq!("\u{2603}".as_bytes(), raw_values[2]);
+
+        for encoding in [Encoding::Utf16, Encoding::Utf16BigEndian, Encoding::Utf16LittleEndian, Encoding::Latin1] {
+            let mut data: Vec<u8> = Vec::new();
+            let mut writer = BinaryWriter::new(&mut data);
+            writer.set_encoding(encoding);
+            writer.write_dynamic(&DynamicValue::Char('A'))?;
+            if encoding != Encoding::Latin1 {
+                writer.write_dynamic(&DynamicValue::Char('\u{1F408}'))?;
+            }
+            writer.write_dynamic(&DynamicValue::Int32(7))?;
+
+            let mut reader = BinaryReader::new(data.as_slice());
+            reader.set_encoding(encoding);
+            let mut copied: Vec<u8> = Vec::new();
+            while !reader.is_at_eof()? {
+                let type_code = reader.read_byte()?;
+                copied.push(type_code);
+                copied.extend(reader.read_raw_for_tag(TypeCode::try_from(type_code)?)?);
+            }
+            assert_eq!(data, copied);
+            // the last value was read from the right position
+            assert_eq!([9, 7, 0, 0, 0], copied[copied.len() - 5..]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn boolean_true_byte() -> Result<(), DataDecodeError> {
+        use std::num::NonZeroU8;
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(1, writer.boolean_true_byte().get());
+        writer.write_boolean(true)?;
+        writer.set_boolean_true_byte(NonZeroU8::MAX);
+        writer.write_boolean(true)?;
+        writer.write_boolean(false)?;
+        assert_eq!(vec![0x01, 0xFF, 0x00], data);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(reader.read_boolean()?);
+        assert!(reader.read_boolean()?);
+        assert!(!reader.read_boolean()?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_string_array() -> Result<(), DataDecodeError> {
+        let strings = ["meow", "", "\u{2603}"];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(14, writer.write_string_array(LengthPrefix::Int32, &strings)?);
+        writer.write_string_array::<&str>(LengthPrefix::SevenBitEncodedInt, &[])?;
+        writer.write_string_array(LengthPrefix::UInt16, &["meowmeowmeowmeowmeow".to_string()])?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(strings.as_slice(), reader.read_string_array(LengthPrefix::Int32)?);
+        assert!(reader.read_string_array(LengthPrefix::SevenBitEncodedInt)?.is_empty());
+        reader.set_max_read_length(10);
+        assert!(matches!(reader.read_string_array(LengthPrefix::UInt16), Err(DataDecodeError::InvalidData(InvalidDataError::LengthLimitExceeded))));
+        Ok(())
+    }
+
+    #[test]
+    fn frames() -> Result<(), DataDecodeError> {
+        use std::io::Cursor;
+        let messages: [&[u8]; 3] = [b"meow", b"", &[0x01, 0x02, 0x03]];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        for message in messages {
+            writer.write_i32(message.len() as i32)?;
+            writer.write_bytes(message)?;
+        }
+
+        let mut reader = BinaryReader::new(Cursor::new(data.clone()));
+        let mut count = 0;
+        for (frame, message) in reader.frames().zip(messages) {
+            assert_eq!(message, frame?);
+            count += 1;
+        }
+        assert_eq!(3, count);
+        assert!(reader.is_at_eof()?);
+        assert!(reader.frames().next().is_none());
+
+        // a truncated body is an error, after which the iterator ends
+        data.pop();
+        let mut reader = BinaryReader::new(Cursor::new(data));
+        let mut frames = reader.frames();
+        assert!(frames.next().unwrap().is_ok());
+        assert!(frames.next().unwrap().is_ok());
+        assert!(matches!(frames.next(), Some(Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes)))));
+        assert!(frames.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn mixed_byte_orders() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_u32_be(0xCAFEBABE)?;
+        writer.write_u16_be(0x1234)?;
+        writer.write_u32(0xCAFEBABE)?;
+        writer.write_i64_le(-2147483649)?;
+        writer.write_f64_be(727.247)?;
+        writer.write_f32_le(5.2)?;
+        writer.write_i16_be(-5)?;
+        assert_eq!(&[0xCA, 0xFE, 0xBA, 0xBE, 0x12, 0x34, 0xBE, 0xBA, 0xFE, 0xCA], &data[0..10]);
+
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order(data.as_slice());
+        assert_eq!(0xCAFEBABE, reader.read_u32()?);
+        assert_eq!(0x1234, reader.read_u16()?);
+        assert_eq!(0xCAFEBABE, reader.read_u32_le()?);
+        assert_eq!(-2147483649, reader.read_i64_le()?);
+        assert_eq!(727.247, reader.read_f64()?);
+        assert_eq!(5.2, reader.read_f32_le()?);
+        assert_eq!(-5, reader.read_i16_be()?);
+        Ok(())
+    }
+
+    #[test]
+    fn rewind_and_skip_buffered() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_i32(-100)?;
+        writer.write_string("meow")?;
+        writer.write_u16(624)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.peek_bytes(data.len())?;
+        reader.skip_buffered(4)?;
+        assert_eq!("meow", reader.read_string()?);
+        reader.rewind_buffered(9)?;
+        assert_eq!(0, reader.num_bytes_read());
+        assert_eq!(-100, reader.read_i32()?);
+        reader.skip_buffered(5)?;
+        assert_eq!(624, reader.read_u16()?);
+        assert!(matches!(reader.skip_buffered(1), Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))));
+        assert!(matches!(reader.rewind_buffered(12), Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))));
+        reader.rewind_buffered(11)?;
+        assert_eq!(data, reader.read_bytes(11)?);
+        Ok(())
+    }
+
+    #[test]
+    fn read_length_prefix() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_7_bit_encoded_int(404)?;
+        writer.write_i32(-1)?;
+        writer.write_u16(624)?;
+        writer.write_7_bit_encoded_int(-5)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(404, reader.read_length_prefix(LengthPrefix::SevenBitEncodedInt, 404)?);
+        assert!(matches!(reader.read_length_prefix(LengthPrefix::Int32, usize::MAX), Err(DataDecodeError::InvalidData(InvalidDataError::NegativeLength))));
+        assert!(matches!(reader.read_length_prefix(LengthPrefix::UInt16, 623), Err(DataDecodeError::InvalidData(InvalidDataError::LengthLimitExceeded))));
+        // a negative string length is an error rather than a panic
+        assert!(matches!(reader.read_string(), Err(DataDecodeError::InvalidData(InvalidDataError::NegativeLength))));
+        Ok(())
+    }
+
+    #[test]
+    fn read_decimal() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_decimal(Decimal::new(-727247, 3)?)?;
+        writer.write_decimal(Decimal::new((1 << 96) - 1, 28)?)?;
+        // reserved bits below the scale
+        writer.write_bytes(&[0; 12])?;
+        writer.write_u32(0x0000_0001)?;
+        // reserved bits between the scale and the sign
+        writer.write_bytes(&[0; 12])?;
+        writer.write_u32(0x0100_0000)?;
+        // a scale greater than 28
+        writer.write_bytes(&[0; 12])?;
+        writer.write_u32(29 << 16)?;
+        assert_eq!(&[0xCF, 0x18, 0x0B, 0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x00, 0x03, 0x80], &data[0..16]);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let value = reader.read_decimal()?;
+        assert_eq!((-727247, 3, true), (value.mantissa(), value.scale(), value.is_negative()));
+        assert_eq!((1 << 96) - 1, reader.read_decimal()?.mantissa());
+        for _ in 0..3 {
+            assert!(matches!(reader.read_decimal(), Err(DataDecodeError::InvalidData(InvalidDataError::InvalidDecimal))));
+        }
+        assert_eq!(Err(InvalidDataError::InvalidDecimal), Decimal::new(1, 29));
+        assert_eq!(Err(InvalidDataError::OutOfRange), Decimal::new(-(1 << 96), 0));
+        Ok(())
+    }
+
+    #[test]
+    fn buffered_section() -> Result<(), DataDecodeError> {
+        use std::num::NonZeroU8;
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.set_boolean_true_byte(NonZeroU8::MAX);
+        let (length, body) = writer.buffered_section(|section| {
+            section.write_boolean(true)?;
+            section.write_string("meow")?;
+            section.write_interned_string("meow")
+        })?;
+        assert_eq!(12, length);
+        assert_eq!(0, writer.num_bytes_written());
+        writer.write_7_bit_encoded_int(length as i32)?;
+        writer.write_bytes(&body)?;
+        // the interned string table is shared with the section
+        assert_eq!(1, writer.write_interned_string("meow")?);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(12, reader.read_7_bit_encoded_int()?);
+        assert_eq!(0xFF, reader.read_byte()?);
+        assert_eq!("meow", reader.read_string()?);
+        assert_eq!("meow", reader.read_interned_string()?);
+        assert_eq!("meow", reader.read_interned_string()?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_string_by_char_count() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(11, writer.write_string_by_char_count(LengthPrefix::Int32, "me\u{2603}ow")?);
+        writer.write_string_by_char_count(LengthPrefix::SevenBitEncodedInt, "\u{1F408}")?;
+        writer.write_7_bit_encoded_int(2)?;
+        writer.write_bytes("\u{2603}".as_bytes())?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!("me\u{2603}ow", reader.read_string_by_char_count(LengthPrefix::Int32)?);
+        assert_eq!("\u{1F408}", reader.read_string_by_char_count(LengthPrefix::SevenBitEncodedInt)?);
+        // the declared count is one more than the data contains
+        assert!(matches!(reader.read_string_by_char_count(LengthPrefix::SevenBitEncodedInt), Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))));
+
+        let data: [u8; 3] = [0x02, b'a', 0x80];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(matches!(reader.read_string_by_char_count(LengthPrefix::SevenBitEncodedInt), Err(DataDecodeError::InvalidData(InvalidDataError::InvalidUtf8))));
+        Ok(())
+    }
+
+    #[test]
+    fn write_slice_le_be() -> Result<(), DataDecodeError> {
+        // compiled with and without the `bytemuck` feature, this covers both the path which
+        // writes straight from memory and the path which converts each value, as a big-endian
+        // target would
+        let ints: [i32; 4] = [-100, 0, 404, i32::MIN];
+        let floats: [f64; 3] = [727.247, -0.0, f64::INFINITY];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(16, writer.write_slice_le(&ints)?);
+        assert_eq!(24, writer.write_slice_be(&floats)?);
+        assert_eq!(0, writer.write_slice_be::<u16>(&[])?);
+        assert_eq!(40, writer.num_bytes_written());
+
+        let mut expected: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut expected);
+        for value in ints {
+            writer.write_i32_le(value)?;
+        }
+        for value in floats {
+            writer.write_f64_be(value)?;
+        }
+        assert_eq!(expected, data);
+        Ok(())
+    }
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn round_trip_compressed_section() -> Result<(), DataDecodeError> {
+        let section = "meow".repeat(100).into_bytes();
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        let written = writer.write_compressed_section(&section)?;
+        assert_eq!(writer.num_bytes_written(), written as u64);
+        assert!(written < section.len());
+        writer.write_compressed_section(&[])?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(section, reader.read_compressed_section()?);
+        assert!(reader.read_compressed_section()?.is_empty());
+
+        // declare one byte more and one byte less than the data decompresses to
+        for declared_len in [401_i32, 399] {
+            let mut corrupt = data.clone();
+            corrupt[0..4].copy_from_slice(&declared_len.to_le_bytes());
+            let mut reader = BinaryReader::new(corrupt.as_slice());
+            assert!(matches!(reader.read_compressed_section(), Err(DataDecodeError::InvalidData(InvalidDataError::InvalidCompressedData))));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn read_bits() -> Result<(), DataDecodeError> {
+        let data: [u8; 3] = [0b1010_1100, 0b0111_0001, 0xFF];
+        let mut reader = BinaryReader::new(data.as_slice());
+        let mut bits = BitReader::new(&mut reader);
+        assert_eq!(0b101, bits.read_bits(3)?);
+        assert_eq!(0b01100, bits.read_bits(5)?);
+        assert_eq!(0b011, bits.read_bits(3)?);
+        // crosses from the second byte into the third
+        assert_eq!(0b1_0001_1111, bits.read_bits(9)?);
+        assert!(bits.read_bit()?);
+        bits.align_to_byte();
+        assert!(matches!(bits.read_bit(), Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))));
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let mut bits = BitReader::with_bit_order(&mut reader, BitOrder::LsbFirst);
+        assert_eq!(0b100, bits.read_bits(3)?);
+        assert_eq!(0b10101, bits.read_bits(5)?);
+        assert_eq!(0b1_0111_0001, bits.read_bits(9)?);
+        assert_eq!(0b1111111, bits.read_bits(7)?);
+        assert!(reader.is_at_eof()?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_bits() -> Result<(), DataDecodeError> {
+        let fields: [(u64, u32); 6] = [(0b101, 3), (0x1FF, 9), (0, 1), (u64::MAX, 64), (0b10, 2), (0x2A, 7)];
+        for bit_order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let mut data: Vec<u8> = Vec::new();
+            let mut writer = BinaryWriter::new(&mut data);
+            let mut bits = BitWriter::with_bit_order(&mut writer, bit_order);
+            let mut written = 0;
+            for (value, num_bits) in fields {
+                written += bits.write_bits(value, num_bits)?;
+            }
+            assert_eq!(10, written);
+            // 86 bits fill 10 bytes, and the last 6 bits are padded to a whole byte
+            assert_eq!(1, bits.flush()?);
+            assert_eq!(0, bits.flush()?);
+            bits.write_bit(true)?;
+            drop(bits);
+            assert_eq!(12, writer.num_bytes_written());
+
+            let mut reader = BinaryReader::new(data.as_slice());
+            let mut bits = BitReader::with_bit_order(&mut reader, bit_order);
+            for (value, num_bits) in fields {
+                assert_eq!(value, bits.read_bits(num_bits)?);
+            }
+            assert_eq!(0, bits.read_bits(2)?);
+            bits.align_to_byte();
+            assert!(bits.read_bit()?);
+            assert_eq!(0, bits.read_bits(7)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn array_of_user_type() -> Result<(), Box<dyn std::error::Error>> {
+        use std::io::{self, Read, Write};
+
+        #[derive(Debug, PartialEq)]
+        struct Point {
+            name: String,
+            x: i32,
+            y: f32,
+        }
+
+        impl CsharpReadable for Point {
+            fn read_from<T: Read, B: ByteOrder>(reader: &mut BinaryReader<T, B>) -> Result<Self, DataDecodeError> {
+                Ok(Self { name: reader.read()?, x: reader.read()?, y: reader.read()? })
+            }
+        }
+
+        impl CsharpWritable for Point {
+            fn write_to<T: Write, B: ByteOrder>(&self, writer: &mut BinaryWriter<T, B>) -> io::Result<usize> {
+                Ok(writer.write(self.name.as_str())? + writer.write(&self.x)? + writer.write(&self.y)?)
+            }
+        }
+
+        let points = vec![
+            Point { name: "origin".to_string(), x: 0, y: 0.0 },
+            Point { name: "p".to_string(), x: -3, y: 1.5 },
+        ];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(29, writer.write_array_of(LengthPrefix::Int32, &points)?);
+        writer.write_array_of(LengthPrefix::SevenBitEncodedInt, &[(1_u8, 'é'), (2, 'x')])?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(points, reader.read_array_of::<Point>(LengthPrefix::Int32)?);
+        assert_eq!(points[1], testutil::round_trip(&points[1]));
+        assert_eq!(vec![(1_u8, 'é'), (2, 'x')], reader.read_array_of::<(u8, char)>(LengthPrefix::SevenBitEncodedInt)?);
+        assert!(reader.is_at_eof()?);
+        Ok(())
+    }
+
+    #[test]
+    fn detect_string_encoding() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        // "hé" in UTF-16 with a byte order mark, in both byte orders
+        writer.write_bytes(&[6, 0xFF, 0xFE, b'h', 0, 0xE9, 0]).unwrap();
+        writer.write_bytes(&[6, 0xFE, 0xFF, 0, b'h', 0, 0xE9]).unwrap();
+        writer.write_bytes(&[5, 0xEF, 0xBB, 0xBF, b'h', b'i']).unwrap();
+        writer.write_string("héllo").unwrap();
+        // "hé" in Latin1 isn't valid UTF-8
+        writer.write_bytes(&[2, b'h', 0xE9]).unwrap();
+        writer.write_bytes(&[0]).unwrap();
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let prefix = LengthPrefix::SevenBitEncodedInt;
+        assert_eq!(("hé".to_string(), Encoding::Utf16LittleEndian), reader.read_string_detect(prefix)?);
+        assert_eq!(("hé".to_string(), Encoding::Utf16BigEndian), reader.read_string_detect(prefix)?);
+        assert_eq!(("hi".to_string(), Encoding::Utf8), reader.read_string_detect(prefix)?);
+        assert_eq!(("héllo".to_string(), Encoding::Utf8), reader.read_string_detect(prefix)?);
+        assert_eq!(("hé".to_string(), Encoding::Latin1), reader.read_string_detect(prefix)?);
+        assert_eq!((String::new(), Encoding::Utf8), reader.read_string_detect(prefix)?);
+        assert!(reader.is_at_eof()?);
+
+        // the detected encoding means the same thing when given back to a big-endian reader
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order(data.as_slice());
+        let (_, encoding) = reader.read_string_detect(prefix)?;
+        assert_eq!(Encoding::Utf16LittleEndian, encoding);
+        let data = [4, b'h', 0, 0xE9, 0];
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order(data.as_slice());
+        reader.set_encoding(encoding);
+        assert_eq!("hé", reader.read_string()?);
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.set_encoding(Encoding::Latin1);
+        writer.write_char('é').unwrap();
+        writer.write_char('€').unwrap();
+        writer.set_encoding(Encoding::Utf16BigEndian);
+        writer.write_char('😀').unwrap();
+        assert_eq!(vec![0xE9, b'?', 0xD8, 0x3D, 0xDE, 0x00], data);
+        Ok(())
+    }
+
+    #[test]
+    fn read_rest_as() -> Result<(), DataDecodeError> {
+        let values = [1.5, -0.25, f64::MAX];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        for value in values {
+            writer.write_f64(value).unwrap();
+        }
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(values.to_vec(), reader.read_rest_as::<f64>()?);
+        assert!(reader.read_rest_as::<f64>()?.is_empty());
+
+        let mut reader = BinaryReader::new(&data[..data.len() - 3]);
+        assert!(matches!(
+            reader.read_rest_as::<f64>(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn fill_buffer() -> Result<(), DataDecodeError> {
+        let data: [u8; 6] = [1, 2, 3, 4, 5, 6];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(&[1, 2, 3, 4], reader.fill_buffer(4)?);
+        assert_eq!(4, reader.num_bytes_read());
+        assert!(matches!(
+            reader.fill_buffer(3),
+            Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))
+        ));
+        assert_eq!(&[5, 6], reader.fill_buffer(2)?);
+        assert!(reader.is_at_eof()?);
+        Ok(())
+    }
+
+    #[test]
+    fn write_generic_primitive() -> Result<(), DataDecodeError> {
+        fn write_all_le<T: std::io::Write, N: Primitive>(writer: &mut BinaryWriter<T>, values: &[N]) -> usize {
+            values.iter().map(|value| writer.write_le(*value).unwrap()).sum()
+        }
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(4, write_all_le(&mut writer, &[-2_i16, 300]));
+        assert_eq!(8, write_all_le(&mut writer, &[u64::MAX]));
+        assert_eq!(8, write_all_le(&mut writer, &[0.5_f32, -1.0]));
+        assert_eq!(4, writer.write_be(7_u32).unwrap());
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(-2, reader.read_i16()?);
+        assert_eq!(300, reader.read_i16()?);
+        assert_eq!(u64::MAX, reader.read_u64()?);
+        assert_eq!(0.5, reader.read_f32()?);
+        assert_eq!(-1.0, reader.read_f32()?);
+        assert_eq!(7, reader.read_u32_be()?);
+        Ok(())
+    }
+
+    #[test]
+    fn strict_floats() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        data.extend_from_slice(&0x7FC0_0000_u32.to_le_bytes());
+        data.extend_from_slice(&f64::INFINITY.to_le_bytes());
+        data.extend_from_slice(&1.5_f64.to_le_bytes());
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(!reader.strict_floats());
+        assert!(reader.read_f32()?.is_nan());
+        assert_eq!(f64::INFINITY, reader.read_f64()?);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.set_strict_floats(true);
+        assert!(matches!(
+            reader.read_f32(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::NonFiniteFloat))
+        ));
+        assert!(matches!(
+            reader.read_f64(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::NonFiniteFloat))
+        ));
+        assert_eq!(1.5, reader.read_f64()?);
+        Ok(())
+    }
+
+    #[test]
+    fn normalized_integers() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_unorm8(1.0).unwrap();
+        writer.write_unorm8(-0.5).unwrap();
+        writer.write_unorm8(f32::NAN).unwrap();
+        writer.write_snorm8(-1.0).unwrap();
+        writer.write_snorm16(2.0).unwrap();
+        writer.write_i8(i8::MIN).unwrap();
+        assert_eq!(vec![255, 0, 0, 0x81, 0xFF, 0x7F, 0x80], data);
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(1.0, reader.read_unorm8()?);
+        assert_eq!(0.0, reader.read_unorm8()?);
+        assert_eq!(0.0, reader.read_unorm8()?);
+        assert_eq!(-1.0, reader.read_snorm8()?);
+        assert_eq!(1.0, reader.read_snorm16()?);
+        assert_eq!(-1.0, reader.read_snorm8()?);
+
+        // every value round trips to within half a step, and every step round trips exactly
+        for i in 0..=1000 {
+            let unorm = i as f32 / 1000.0;
+            let snorm = unorm * 2.0 - 1.0;
+            let mut data: Vec<u8> = Vec::new();
+            let mut writer = BinaryWriter::new(&mut data);
+            writer.write_unorm8(unorm).unwrap();
+            writer.write_unorm16(unorm).unwrap();
+            writer.write_snorm8(snorm).unwrap();
+            writer.write_snorm16(snorm).unwrap();
+            let mut reader = BinaryReader::new(data.as_slice());
+            assert!((reader.read_unorm8()? - unorm).abs() <= 0.5 / 255.0 + f32::EPSILON);
+            assert!((reader.read_unorm16()? - unorm).abs() <= 0.5 / 65535.0 + f32::EPSILON);
+            assert!((reader.read_snorm8()? - snorm).abs() <= 0.5 / 127.0 + f32::EPSILON);
+            assert!((reader.read_snorm16()? - snorm).abs() <= 0.5 / 32767.0 + f32::EPSILON);
+        }
+        for step in 0..=u16::MAX {
+            let value = BinaryReader::new(step.to_le_bytes().as_slice()).read_unorm16()?;
+            let mut data: Vec<u8> = Vec::new();
+            BinaryWriter::new(&mut data).write_unorm16(value).unwrap();
+            assert_eq!(step.to_le_bytes().as_slice(), data);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn chunk_headers() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(8, writer.write_chunk_header(*b"fmt ", 3).unwrap());
+        writer.write_bytes(&[1, 2, 3]).unwrap();
+        writer.write_chunk_header(*b"data", 2).unwrap();
+        writer.write_i16(-5).unwrap();
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let (id, size) = reader.read_chunk_header()?;
+        assert_eq!((*b"fmt ", 3), (id, size));
+        reader.read_bytes(size as usize)?;
+        assert_eq!((*b"data", 2), reader.read_chunk_header()?);
+        assert_eq!(-5, reader.read_i16()?);
+
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order([b'F', b'O', b'R', b'M', 0, 0, 1, 0].as_slice());
+        assert_eq!((*b"FORM", 256), reader.read_chunk_header()?);
+        Ok(())
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn trace_reads_and_writes() -> Result<(), DataDecodeError> {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .without_time()
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            let mut data: Vec<u8> = Vec::new();
+            let mut writer = BinaryWriter::new(&mut data);
+            writer.write_i32(-7).unwrap();
+            writer.write_string("meow").unwrap();
+            let mut reader = BinaryReader::new(data.as_slice());
+            reader.read_i32()?;
+            reader.read_string()?;
+            assert!(reader.read_f64().is_err());
+            Ok::<(), DataDecodeError>(())
+        })?;
+
+        let trace = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        assert!(trace.contains("wrote i32 offset=0 value=-7 len=4"), "{trace}");
+        assert!(trace.contains("wrote string offset=4 value=\"meow\" len=5"), "{trace}");
+        assert!(trace.contains("read i32 offset=0 value=-7"), "{trace}");
+        assert!(trace.contains("read 7-bit encoded int offset=4 value=4"), "{trace}");
+        assert!(trace.contains("read string offset=4 value=\"meow\""), "{trace}");
+        assert!(trace.contains("failed to read f64 offset=9"), "{trace}");
+        Ok(())
+    }
+
+    #[test]
+    fn expect_values() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_i32(3).unwrap();
+        writer.write_u64(u64::MAX).unwrap();
+        writer.write_i16(-1).unwrap();
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.expect_i32(3)?;
+        reader.expect_u64(u64::MAX)?;
+        match reader.expect_i16(0) {
+            Err(DataDecodeError::InvalidData(error)) => {
+                assert_eq!(InvalidDataError::UnexpectedValue { expected: 0, found: -1 }, error);
+                assert_eq!("expected value 0, found -1", error.to_string());
+            },
+            other => panic!("expected an error, got {other:?}"),
+        }
+        assert!(reader.is_at_eof()?);
+        Ok(())
+    }
+
+    #[test]
+    fn complex_round_trip() -> Result<(), DataDecodeError> {
+        let value = Complex::new(1.5, -2.25);
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::<_, BigEndian>::with_byte_order(&mut data);
+        assert_eq!(16, writer.write_complex(value).unwrap());
+        assert_eq!(1.5_f64.to_be_bytes().as_slice(), &data[..8]);
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order(data.as_slice());
+        assert_eq!(value, reader.read_complex()?);
+        Ok(())
+    }
+
+    #[cfg(feature = "num-complex")]
+    #[test]
+    fn num_complex_conversions() {
+        let value = num_complex::Complex64::new(0.5, 3.0);
+        let converted = Complex::from(value);
+        assert_eq!(Complex::new(0.5, 3.0), converted);
+        assert_eq!(value, num_complex::Complex64::from(converted));
+    }
+
+    #[test]
+    fn delta_encoded_i32s() -> Result<(), DataDecodeError> {
+        let increasing = [1000, 1001, 1003, 1010];
+        let decreasing = [50, 20, -10, -400];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        // 1000 takes two bytes, and each delta after it one
+        assert_eq!(5, writer.write_delta_i32_slice(&increasing).unwrap());
+        writer.write_delta_i32_slice(&decreasing).unwrap();
+        assert_eq!(
+            std::io::ErrorKind::InvalidInput,
+            writer.write_delta_i32_slice(&[i32::MIN, i32::MAX]).unwrap_err().kind()
+        );
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(increasing.to_vec(), reader.read_delta_i32_vec(4)?);
+        assert_eq!(decreasing.to_vec(), reader.read_delta_i32_vec(4)?);
+        assert!(reader.is_at_eof()?);
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_7_bit_encoded_int(i32::MAX).unwrap();
+        writer.write_7_bit_encoded_int(1).unwrap();
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(matches!(
+            reader.read_delta_i32_vec(2),
+            Err(DataDecodeError::InvalidData(InvalidDataError::IntegerOverflow))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn utf16_strings_and_chars() -> Result<(), DataDecodeError> {
+        for encoding in [Encoding::Utf16, Encoding::Utf16BigEndian, Encoding::Utf16LittleEndian] {
+            let mut data: Vec<u8> = Vec::new();
+            let mut writer = BinaryWriter::new(&mut data);
+            writer.set_encoding(encoding);
+            assert_eq!(4, writer.write_char('\u{1D11E}').unwrap());
+            // 3 chars in 4 code units, prefixed by the number of bytes
+            assert_eq!(9, writer.write_string("a\u{1D11E}é").unwrap());
+
+            let mut reader = BinaryReader::new(data.as_slice());
+            reader.set_encoding(encoding);
+            assert_eq!('\u{1D11E}', reader.read_char()?);
+            assert_eq!("a\u{1D11E}é", reader.read_string()?);
+            assert!(reader.is_at_eof()?);
+        }
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.set_encoding(Encoding::Utf16);
+        writer.write_char('\u{1D11E}').unwrap();
+        assert_eq!(vec![0x34, 0xD8, 0x1E, 0xDD], data);
+
+        // an unpaired high surrogate followed by 'a'
+        let mut reader = BinaryReader::new([0x34, 0xD8, b'a', 0, 4, 0x34, 0xD8, b'a', 0].as_slice());
+        reader.set_encoding(Encoding::Utf16);
+        assert!(matches!(reader.read_char(), Err(DataDecodeError::InvalidData(InvalidDataError::InvalidUtf16))));
+        assert!(matches!(reader.read_string(), Err(DataDecodeError::InvalidData(InvalidDataError::InvalidUtf16))));
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.set_encoding(Encoding::Latin1);
+        writer.write_string("é€").unwrap();
+        assert_eq!(vec![2, 0xE9, b'?'], data);
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.set_encoding(Encoding::Latin1);
+        assert_eq!("é?", reader.read_string()?);
+        Ok(())
+    }
+
+    #[test]
+    fn read_bytes_append() -> Result<(), DataDecodeError> {
+        let data: [u8; 7] = [1, 2, 3, 4, 5, 6, 7];
+        let mut reader = BinaryReader::new(data.as_slice());
+        let mut output: Vec<u8> = vec![0];
+        reader.read_bytes_append(2, &mut output)?;
+        reader.read_bytes_append(0, &mut output)?;
+        reader.read_bytes_append(3, &mut output)?;
+        assert!(matches!(
+            reader.read_bytes_append(3, &mut output),
+            Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))
+        ));
+        reader.read_bytes_append(2, &mut output)?;
+        assert_eq!(vec![0, 1, 2, 3, 4, 5, 6, 7], output);
+        Ok(())
+    }
+
+    #[test]
+    fn guid_forms() -> Result<(), DataDecodeError> {
+        let guid: Guid = "00112233-4455-6677-8899-AABBCCDDEEFF".parse()?;
+        assert_eq!(
+            [0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF],
+            guid.to_bytes()
+        );
+        assert_eq!("00112233-4455-6677-8899-aabbccddeeff", guid.to_string());
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(16, writer.write_guid(guid).unwrap());
+        assert_eq!(37, writer.write_guid_string(guid).unwrap());
+        writer.write_string("00112233-4455-6677-8899+aabbccddeeff").unwrap();
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(guid, reader.read_guid()?);
+        assert_eq!(guid, reader.read_guid_string()?);
+        assert!(matches!(
+            reader.read_guid_string(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::InvalidGuid))
+        ));
+        for invalid in ["", "00112233-4455-6677-8899-aabbccddeef", "0011223g-4455-6677-8899-aabbccddeeff", "001122334-455-6677-8899-aabbccddeeff"] {
+            assert_eq!(Err(InvalidDataError::InvalidGuid), invalid.parse::<Guid>());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn buffered_len() -> Result<(), DataDecodeError> {
+        let data: [u8; 6] = [1, 0, 0, 0, 2, 3];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(0, reader.buffered_len());
+        reader.peek_bytes(2)?;
+        let buffered = reader.buffered_len();
+        assert!(buffered >= 2);
+        assert_eq!(1, reader.read_i32()?);
+        assert_eq!(buffered - 4, reader.buffered_len());
+        reader.read_bytes(2)?;
+        assert_eq!(0, reader.buffered_len());
+        assert!(reader.is_at_eof()?);
+        Ok(())
+    }
+
+    #[test]
+    fn borrowed_writer_left_open() {
+        use std::io::{BufWriter, Write};
+
+        let mut output = BufWriter::new(Vec::new());
+        let mut writer = BinaryWriter::new(&mut output);
+        writer.write_i32(5).unwrap();
+        drop(writer);
+        // dropping the BinaryWriter neither flushes nor closes the borrowed Writer
+        assert!(output.get_ref().is_empty());
+        output.write_all(&[6]).unwrap();
+        output.flush().unwrap();
+        assert_eq!(&vec![5, 0, 0, 0, 6], output.get_ref());
+
+        let mut output = BufWriter::new(Vec::new());
+        let mut writer = BinaryWriter::new(&mut output);
+        writer.write_byte(7).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(&vec![7], output.get_ref());
+    }
+
+    #[test]
+    fn u32_prefixed_strings() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(10, writer.write_string_u32_prefixed("héllo").unwrap());
+        assert_eq!(4, writer.write_string_u32_prefixed("").unwrap());
+        writer.write_u32(2).unwrap();
+        writer.write_bytes(&[0xC3, 0x28]).unwrap();
+        writer.write_u32(u32::MAX).unwrap();
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!("héllo", reader.read_string_u32_prefixed()?);
+        assert_eq!("", reader.read_string_u32_prefixed()?);
+        assert!(matches!(
+            reader.read_string_u32_prefixed(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::InvalidUtf8))
+        ));
+        reader.set_max_read_length(1024);
+        assert!(matches!(
+            reader.read_string_u32_prefixed(),
+            Err(DataDecodeError::InvalidData(InvalidDataError::LengthLimitExceeded))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn planned_records() -> Result<(), DataDecodeError> {
+        let schema = [
+            FieldType::I32, FieldType::Boolean, FieldType::F64, FieldType::String,
+            FieldType::U16, FieldType::Bytes(3), FieldType::I8, FieldType::SevenBitEncodedInt, FieldType::F32,
+        ];
+        let record = vec![
+            FieldValue::I32(-7), FieldValue::Boolean(true), FieldValue::F64(2.5), FieldValue::String("meow".to_string()),
+            FieldValue::U16(600), FieldValue::Bytes(vec![1, 2, 3]), FieldValue::I8(-1), FieldValue::SevenBitEncodedInt(300), FieldValue::F32(f32::NAN),
+        ];
+        let plan = RecordPlan::new(&schema);
+        assert_eq!(None, plan.fixed_size());
+        assert_eq!(Some(17), RecordPlan::new(&[FieldType::U64, FieldType::Bytes(5), FieldType::F32]).fixed_size());
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::<_, BigEndian>::with_byte_order(&mut data);
+        for _ in 0..2 {
+            writer.write_record(&record).unwrap();
+        }
+
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order(data.as_slice());
+        let planned = reader.read_planned_record(&plan)?;
+        assert_eq!(format!("{:?}", reader.read_record(&schema)?), format!("{planned:?}"));
+        assert_eq!(&record[..8], &planned[..8]);
+        assert!(reader.is_at_eof()?);
+
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order(data.as_slice());
+        reader.set_strict_floats(true);
+        assert!(matches!(
+            reader.read_planned_record(&plan),
+            Err(DataDecodeError::InvalidData(InvalidDataError::NonFiniteFloat))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_7_bit_encoded_int() {
+        let data = [0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(matches!(reader.peek_7_bit_encoded_int_len(), Err(DataDecodeError::InvalidData(InvalidDataError::MalformedVarint))));
+        assert!(matches!(reader.read_7_bit_encoded_int(), Err(DataDecodeError::InvalidData(InvalidDataError::MalformedVarint))));
+
+        let data = [0xFF; 11];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(matches!(reader.read_7_bit_encoded_int64(), Err(DataDecodeError::InvalidData(InvalidDataError::MalformedVarint))));
+
+        // a terminating byte which is too large is still an overflow
+        let data = [0xFF, 0xFF, 0xFF, 0xFF, 0x7F];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(matches!(reader.read_7_bit_encoded_int(), Err(DataDecodeError::InvalidData(InvalidDataError::IntegerOverflow))));
+    }
+
+    #[test]
+    fn peek_type_code() -> Result<(), DataDecodeError> {
+        let data = [9, 42, 0, 0, 0, 2];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(TypeCode::Int32, reader.peek_type_code()?);
+        assert_eq!(DynamicValue::Int32(42), reader.read_dynamic()?);
+
+        assert!(matches!(reader.peek_type_code(), Err(DataDecodeError::InvalidData(InvalidDataError::UnknownTypeCode))));
+        assert_eq!(2, reader.read_byte()?);
+        Ok(())
+    }
+
+    #[test]
+    fn pairs_until_eof() -> Result<(), DataDecodeError> {
+        let pairs = [("one", 1), ("two", 2), ("three", 3)];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_pairs(pairs, |w, k| w.write_string(k), |w, v| w.write_i32(v))?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let read = reader.read_pairs_until_eof(|r| r.read_string(), |r| r.read_i32())?;
+        assert_eq!(pairs.map(|(k, v)| (k.to_string(), v)).to_vec(), read);
+
+        let truncated = &data[..data.len() - 2];
+        let mut reader = BinaryReader::new(truncated);
+        let result = reader.read_pairs_until_eof(|r| r.read_string(), |r| r.read_i32());
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))));
+        Ok(())
+    }
+
+    #[test]
+    fn reset_reader_and_writer() -> Result<(), DataDecodeError> {
+        use std::io::Cursor;
+        let mut writer = BinaryWriter::new(Vec::new());
+        writer.write_string("first")?;
+        let first = writer.reset(Vec::new());
+        writer.write_i32(7)?;
+        assert_eq!(4, writer.num_bytes_written());
+        let second = writer.reset(Vec::new());
+
+        let mut reader = BinaryReader::new(Cursor::new(first));
+        assert_eq!(b"fir", &reader.peek_bytes(4)?[1..]);
+        let old = reader.reset(Cursor::new(second));
+        assert_eq!(0, reader.num_bytes_read());
+        assert_eq!(7, reader.read_i32()?);
+        assert!(reader.is_at_eof()?);
+
+        reader.reset(Cursor::new(old.into_inner()));
+        assert_eq!("first", reader.read_string()?);
+        assert_eq!(6, reader.num_bytes_read());
+        Ok(())
+    }
+
+    #[test]
+    fn flags_enum() -> Result<(), DataDecodeError> {
+        const VALID: u64 = 0b1011;
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_flags_enum(0b1001_i32, VALID)?;
+        assert!(writer.write_flags_enum(0b0100_i32, VALID).is_err());
+        writer.write_i32(0b1101)?;
+        writer.write_i32(-1)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(0b1001, reader.read_flags_enum::<i32>(VALID)?);
+        assert!(matches!(reader.read_flags_enum::<i32>(VALID), Err(DataDecodeError::InvalidData(InvalidDataError::InvalidFlags))));
+        assert_eq!(-1, reader.read_flags_enum::<i32>(u32::MAX.into())?);
+        Ok(())
+    }
+
+    #[test]
+    fn stream_len() -> Result<(), DataDecodeError> {
+        use std::io::{Cursor, Seek};
+        let mut reader = BinaryReader::new(Cursor::new([1_u8, 0, 0, 0, 2, 0, 0, 0, 3]));
+        assert_eq!(1, reader.read_i32()?);
+        let position = reader.stream_position()?;
+        assert_eq!(9, reader.stream_len()?);
+        assert_eq!(position, reader.stream_position()?);
+        assert_eq!(2, reader.read_i32()?);
+        assert_eq!(3, reader.read_byte()?);
+        Ok(())
+    }
+
+    #[test]
+    fn is_present_markers() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_is_present(false)?;
+        writer.write_is_present(true)?;
+        writer.write_optional(None::<&str>, |w, s| w.write_string(s))?;
+        writer.write_optional(Some("meow"), |w, s| w.write_string(s))?;
+        assert_eq!([0, 1, 0, 1, 4], data[..5]);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(!reader.read_is_present()?);
+        assert!(reader.read_is_present()?);
+        assert_eq!(None, reader.read_optional(|r| r.read_string())?);
+        assert_eq!(Some("meow".to_string()), reader.read_optional(|r| r.read_string())?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_helper() {
+        use testutil::round_trip;
+        assert_eq!(-42, round_trip(&-42_i32));
+        assert_eq!(u64::MAX, round_trip(&u64::MAX));
+        assert_eq!('é', round_trip(&'é'));
+        assert_eq!("meow", round_trip(&"meow".to_string()));
+        assert_eq!((true, 7.27_f64, -3_i8), round_trip(&(true, 7.27_f64, -3_i8)));
+    }
+
+    #[test]
+    fn parse_random_bytes() {
+        // xorshift, so the test is reproducible without a dependency
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..500 {
+            let len = (next() % 64) as usize;
+            let data: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            testutil::try_parse_all(&data);
+        }
+        // varints and surrogates are unlikely to come up by chance
+        testutil::try_parse_all(&[0xFF; 16]);
+        testutil::try_parse_all(&[0x01, 0x00, 0xD8, 0x00, 0xD8, 0x00, 0xDC]);
+        assert!(testutil::try_parse_all(&[]).is_empty());
+    }
+
+    #[test]
+    fn malformed_data_errors_without_panicking() {
+        fn is_invalid<R: std::fmt::Debug>(result: Result<R, DataDecodeError>) -> bool {
+            matches!(result, Err(DataDecodeError::InvalidData(_)))
+        }
+        assert!(is_invalid(BinaryReader::new([0x01].as_slice()).read_i16()));
+        assert!(is_invalid(BinaryReader::new([0x01, 0x02, 0x03].as_slice()).read_f32()));
+        assert!(is_invalid(BinaryReader::new([0xC3].as_slice()).read_char()));
+        assert!(is_invalid(BinaryReader::new([0xFF, 0xFF, 0xFF, 0xFF].as_slice()).read_char()));
+        assert!(is_invalid(BinaryReader::new([0x05, b'a'].as_slice()).read_string()));
+        assert!(is_invalid(BinaryReader::new([0x01, 0x02].as_slice()).read_chunk_header()));
+        assert!(is_invalid(BinaryReader::new([0x00; 15].as_slice()).read_guid()));
+        assert!(is_invalid(BinaryReader::new([0x00; 3].as_slice()).read_color(ColorLayout::Bytes)));
+        assert!(is_invalid(bytes::read_u64_le(&[0x00; 7]).map_err(DataDecodeError::from)));
+
+        let mut reader = BinaryReader::new([0x03, b'h', 0x00, 0x00, 0x00, 0xD8].as_slice());
+        reader.set_encoding(Encoding::Utf16);
+        assert!(is_invalid(reader.read_string()));
+        assert!(is_invalid(reader.read_char()));
+
+        let mut reader = BinaryReader::new([0x01, 0x00, 0x00].as_slice());
+        assert!(is_invalid(reader.read_record(&[FieldType::Boolean, FieldType::I32])));
+        let plan = RecordPlan::new(&[FieldType::U8, FieldType::I8, FieldType::U16]);
+        let mut reader = BinaryReader::new([0x01, 0x00, 0x00].as_slice());
+        assert!(is_invalid(reader.read_planned_record(&plan)));
+    }
+
+    #[test]
+    fn versioned_reader() -> Result<(), DataDecodeError> {
+        fn read_item<T: std::io::Read>(reader: &mut BinaryReader<T>) -> Result<(String, i32, Option<f32>), DataDecodeError> {
+            let mut reader = VersionedReader::read_version(reader)?;
+            let name = reader.read_string()?;
+            let count = reader.read_i32()?;
+            let weight = reader.if_version_gte(2, |r| r.read_f32())?;
+            Ok((name, count, weight))
+        }
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_u32(2)?;
+        writer.write_string("meow")?;
+        writer.write_i32(3)?;
+        writer.write_f32(1.5)?;
+        writer.write_u32(1)?;
+        writer.write_string("old")?;
+        writer.write_i32(-1)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(("meow".to_string(), 3, Some(1.5)), read_item(&mut reader)?);
+        assert_eq!(("old".to_string(), -1, None), read_item(&mut reader)?);
+        assert!(reader.is_at_eof()?);
+        Ok(())
+    }
+
+    #[test]
+    fn tolerant_7_bit_encoded_int() -> Result<(), DataDecodeError> {
+        // -1 with extra bits set in the last byte, as some old writers emit it
+        let data = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xFF];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(!reader.tolerant_7_bit_ints());
+        assert!(matches!(reader.read_7_bit_encoded_int(), Err(DataDecodeError::InvalidData(InvalidDataError::MalformedVarint))));
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.set_tolerant_7_bit_ints(true);
+        assert_eq!(5, reader.peek_7_bit_encoded_int_len()?);
+        assert_eq!(-1, reader.read_7_bit_encoded_int()?);
+        assert_eq!(i64::MIN + 1, reader.read_7_bit_encoded_int64()?);
+        Ok(())
+    }
+
+    #[test]
+    fn array_writer_checks_count() -> Result<(), Box<dyn std::error::Error>> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        let mut array = writer.begin_array(2)?;
+        array.write_element(|w| w.write_string("a"))?;
+        array.write_element(|w| w.write_string("bc"))?;
+        assert!(array.write_element(|w| w.write_string("d")).is_err());
+        assert_eq!(9, array.finish()?);
+
+        let mut array = writer.begin_array(3)?;
+        array.write_element(|w| w.write_i16(1))?;
+        array.write_element(|w| w.write_i16(2))?;
+        assert_eq!(1, array.remaining());
+        let error = array.finish().unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidInput, error.kind());
+        assert!(writer.begin_array(-1).is_err());
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(vec!["a".to_string(), "bc".to_string()], reader.read_string_array(LengthPrefix::Int32)?);
+        Ok(())
+    }
+
+    #[test]
+    fn read_nested_payload() -> Result<(), Box<dyn std::error::Error>> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_string("parent")?;
+        let (length, payload) = writer.buffered_section(|w| {
+            w.write_i32(7)?;
+            w.write_string("child")
+        })?;
+        writer.write_u16(length.try_into()?)?;
+        writer.write_bytes(&payload)?;
+        writer.write_boolean(true)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.set_strict_floats(true);
+        assert_eq!("parent", reader.read_string()?);
+        let mut nested = reader.read_nested(LengthPrefix::UInt16)?;
+        assert!(nested.strict_floats());
+        assert_eq!(7, nested.read_i32()?);
+        assert_eq!("child", nested.read_string()?);
+        // the nested reader can't read into the rest of the parent
+        assert!(nested.read_boolean().is_err());
+        assert!(reader.read_boolean()?);
+        Ok(())
+    }
+
+    #[test]
+    fn utf16_follows_byte_order() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::<_, BigEndian>::with_byte_order(&mut data);
+        writer.set_encoding(Encoding::Utf16);
+        writer.write_string("hé")?;
+        writer.write_char('😺')?;
+        writer.set_encoding(Encoding::Utf8);
+        writer.write_string("hé")?;
+        assert_eq!([4, 0, b'h', 0, 0xE9, 0xD8, 0x3D, 0xDE, 0x3A, 3, b'h', 0xC3, 0xA9], data.as_slice());
+
+        let mut reader = BinaryReader::<_, BigEndian>::with_byte_order(data.as_slice());
+        reader.set_encoding(Encoding::Utf16);
+        assert_eq!("hé", reader.read_string()?);
+        assert_eq!('😺', reader.read_char()?);
+        reader.set_encoding(Encoding::Utf8);
+        assert_eq!("hé", reader.read_string()?);
+
+        // the explicitly big-endian encoding doesn't depend on the byte order
+        let mut little: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut little);
+        writer.set_encoding(Encoding::Utf16BigEndian);
+        writer.write_string("hé")?;
+        writer.write_char('😺')?;
+        assert_eq!(data[..9], little);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_sparse() -> Result<(), DataDecodeError> {
+        let values = [None, Some("one".to_string()), None, None, Some("four".to_string()), None];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(21, writer.write_sparse(&values, |w, v| w.write_string(v))?);
+        writer.write_sparse(&[Some(1_u8), None, Some(3)], |w, v| w.write_byte(*v))?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(values.to_vec(), reader.read_sparse(values.len(), |r| r.read_string())?);
+        // the array is shorter than the written index 2
+        let result = reader.read_sparse(2, |r| r.read_byte());
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::IndexOutOfRange))));
+        Ok(())
+    }
+
+    #[test]
+    fn measured_read() -> Result<(), DataDecodeError> {
+        let long = "a".repeat(200);
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_string("meow")?;
+        writer.write_string(&long)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(("meow".to_string(), 1 + 4), reader.measured(|r| r.read_string())?);
+        assert_eq!((long, 2 + 200), reader.measured(|r| r.read_string())?);
+        Ok(())
+    }
+
+    #[test]
+    fn measured_write() -> Result<(), DataDecodeError> {
+        let mut writer = BinaryWriter::new(Vec::new());
+        let mut offsets = Vec::new();
+        for name in ["a", "bcd", ""] {
+            let ((), len) = writer.measured(|w| {
+                w.write_string(name)?;
+                w.write_i64(-1)?;
+                w.write_boolean(true)?;
+                Ok(())
+            })?;
+            offsets.push(len);
+        }
+        assert_eq!(vec![11, 13, 10], offsets);
+        assert_eq!(34, writer.num_bytes_written());
+        Ok(())
+    }
+
+    #[test]
+    fn read_or_default_at_eof() -> Result<(), DataDecodeError> {
+        let data = [0x2A, 0x00, 0x00, 0x00, 0x01, 0x00];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(42, reader.read_i32_or(-1)?);
+        // a truncated value is still an error
+        assert!(matches!(reader.read_i32_or(-1), Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))));
+
+        let mut reader = BinaryReader::new(&data[..4]);
+        assert_eq!(42, reader.read_i32_or(-1)?);
+        assert_eq!(-1, reader.read_i32_or(-1)?);
+        assert_eq!("none", reader.read_string_or("none".to_string())?);
+        assert_eq!((1.5, 'x'), reader.read_or((1.5_f32, 'x'))?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_nullable_string() -> Result<(), DataDecodeError> {
+        for (null_string_encoding, null_len) in [(NullStringEncoding::PresenceFlag, 1), (NullStringEncoding::SentinelLength, 5)] {
+            let mut data: Vec<u8> = Vec::new();
+            let mut writer = BinaryWriter::new(&mut data);
+            writer.set_null_string_encoding(null_string_encoding);
+            assert_eq!(null_len, writer.write_nullable_string(None)?);
+            writer.write_nullable_string(Some("meow"))?;
+            writer.write_nullable_string(Some(""))?;
+
+            let mut reader = BinaryReader::new(data.as_slice());
+            reader.set_null_string_encoding(null_string_encoding);
+            assert_eq!(None, reader.read_nullable_string()?);
+            assert_eq!(Some("meow".to_string()), reader.read_nullable_string()?);
+            assert_eq!(Some(String::new()), reader.read_nullable_string()?);
+            assert!(reader.is_at_eof()?);
+        }
+
+        let mut reader = BinaryReader::new([0xFE, 0xFF, 0xFF, 0xFF, 0x0F].as_slice());
+        reader.set_null_string_encoding(NullStringEncoding::SentinelLength);
+        assert!(matches!(reader.read_nullable_string(), Err(DataDecodeError::InvalidData(InvalidDataError::NegativeLength))));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_guid_array() -> Result<(), DataDecodeError> {
+        let guids: Vec<Guid> = (0..100_u8).map(|i| Guid::from_bytes([i; 16])).collect();
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(4 + 1600, writer.write_guid_array(LengthPrefix::Int32, &guids)?);
+        writer.write_guid_array(LengthPrefix::SevenBitEncodedInt, &[])?;
+        writer.write_i32(3)?;
+        writer.write_guid(guids[0])?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(guids, reader.read_guid_array(LengthPrefix::Int32)?);
+        assert!(reader.read_guid_array(LengthPrefix::SevenBitEncodedInt)?.is_empty());
+        assert!(matches!(reader.read_guid_array(LengthPrefix::Int32), Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_bit_array() -> Result<(), DataDecodeError> {
+        let bits: Vec<bool> = (0..13).map(|i| i % 3 == 0 || i == 12).collect();
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(2, writer.write_bit_array(&bits)?);
+        // bits 0, 3, 6, then 9 and 12, with the three padding bits left clear
+        assert_eq!([0b0100_1001, 0b0001_0010], data.as_slice());
+        data[1] |= 0b1110_0000;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(bits, reader.read_bit_array(13)?);
+        assert!(reader.is_at_eof()?);
+        Ok(())
+    }
+
+    #[test]
+    fn assert_position_detects_misalignment() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_i32(1)?;
+        writer.write_i32(2)?;
+        writer.write_i64(3)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.read_i32()?;
+        reader.assert_position(4)?;
+        // the second field is misread as a short
+        reader.read_i16()?;
+        let error = reader.assert_position(8).unwrap_err();
+        assert!(matches!(error, DataDecodeError::InvalidData(InvalidDataError::MisalignedStream { expected: 8, actual: 6 })));
+        assert_eq!("expected to be at position 8, but 6 bytes were read", error.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_enum_array() -> Result<(), DataDecodeError> {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        enum Suit {
+            Hearts = 1,
+            Spades = 4,
+        }
+
+        impl From<Suit> for i32 {
+            fn from(suit: Suit) -> i32 {
+                suit as i32
+            }
+        }
+
+        impl TryFrom<i32> for Suit {
+            type Error = ();
+
+            fn try_from(value: i32) -> Result<Self, ()> {
+                match value {
+                    1 => Ok(Suit::Hearts),
+                    4 => Ok(Suit::Spades),
+                    _ => Err(()),
+                }
+            }
+        }
+
+        let suits = [Suit::Spades, Suit::Hearts, Suit::Spades];
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(13, writer.write_enum_array(LengthPrefix::SevenBitEncodedInt, &suits)?);
+        writer.write_i32(2)?;
+        writer.write_enum(Suit::Hearts)?;
+        writer.write_i32(3)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(suits.to_vec(), reader.read_enum_array::<Suit>(LengthPrefix::SevenBitEncodedInt)?);
+        let result = reader.read_enum_array::<Suit>(LengthPrefix::Int32);
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::InvalidEnumValue))));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_soa() -> Result<(), DataDecodeError> {
+        #[derive(Debug, Default, PartialEq)]
+        struct Sample {
+            id: i32,
+            value: f64,
+            flag: bool,
+        }
+
+        let rows: Vec<Sample> = (0..5).map(|i| Sample { id: i * 10, value: i as f64 / 4.0, flag: i % 2 == 0 }).collect();
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        let written = writer.write_soa(&rows, &mut [
+            &mut |w, row: &Sample| w.write_i32(row.id),
+            &mut |w, row: &Sample| w.write_f64(row.value),
+            &mut |w, row: &Sample| w.write_boolean(row.flag),
+        ])?;
+        assert_eq!(5 * (4 + 8 + 1), written);
+        // Columns are stored one after another.
+        assert_eq!(10i32.to_le_bytes(), data[4..8]);
+        assert_eq!(0.25f64.to_le_bytes(), data[28..36]);
+        assert_eq!([1, 0, 1, 0, 1], data[60..65]);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let read: Vec<Sample> = reader.read_soa(5, &mut [
+            &mut |r, row: &mut Sample| { row.id = r.read_i32()?; Ok(()) },
+            &mut |r, row: &mut Sample| { row.value = r.read_f64()?; Ok(()) },
+            &mut |r, row: &mut Sample| { row.flag = r.read_boolean()?; Ok(()) },
+        ])?;
+        assert_eq!(rows, read);
+        assert!(reader.is_at_eof()?);
+        Ok(())
+    }
+
+    #[test]
+    fn unsigned_string_lengths() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_7_bit_encoded_uint(u32::MAX)?;
+        let long_string = "x".repeat(70_000);
+        writer.write_string(&long_string)?;
+        writer.write_7_bit_encoded_uint(0x8000_0000)?;
+        assert_eq!([0xFF, 0xFF, 0xFF, 0xFF, 0x0F], data[..5]);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        reader.set_unsigned_string_lengths(true);
+        assert_eq!(u32::MAX, reader.read_7_bit_encoded_uint()?);
+        // A three byte length prefix decodes the same way as the signed decoder.
+        assert_eq!(long_string, reader.read_string()?);
+        let result = reader.read_string();
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::LengthLimitExceeded))));
+
+        let mut reader = BinaryReader::new(&data[5..]);
+        assert_eq!(long_string, reader.read_string()?);
+        let result = reader.read_string();
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::NegativeLength))));
+
+        // read_raw_for_tag decodes string lengths like read_dynamic does
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_dynamic(&DynamicValue::String(long_string.clone()))?;
+        writer.write_byte(TypeCode::String as u8)?;
+        writer.write_7_bit_encoded_uint(0x8000_0000)?;
+        for unsigned in [false, true] {
+            let mut reader = BinaryReader::new(data.as_slice());
+            reader.set_unsigned_string_lengths(unsigned);
+            reader.read_byte()?;
+            assert_eq!(data[1..data.len() - 6], reader.read_raw_for_tag(TypeCode::String)?);
+            reader.read_byte()?;
+            let raw = reader.read_raw_for_tag(TypeCode::String).unwrap_err();
+            let mut reader = BinaryReader::new(&data[data.len() - 6..]);
+            reader.set_unsigned_string_lengths(unsigned);
+            let dynamic = reader.read_dynamic().unwrap_err();
+            assert_eq!(format!("{dynamic:?}"), format!("{raw:?}"));
+            let expected = if unsigned { InvalidDataError::LengthLimitExceeded } else { InvalidDataError::NegativeLength };
+            assert!(matches!(raw, DataDecodeError::InvalidData(error) if error == expected));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn read_vec_small() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        for values in [&[7, 8][..], &[1, 2, 3, 4, 5, 6]] {
+            writer.write_7_bit_encoded_int(values.len() as i32)?;
+            for &value in values {
+                writer.write_u16(value)?;
+            }
+        }
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let short: smallvec::SmallVec<[u16; 4]> = reader.read_vec_small(LengthPrefix::SevenBitEncodedInt, |r| r.read_u16())?;
+        assert_eq!([7, 8], short.as_slice());
+        assert!(!short.spilled());
+        let long: smallvec::SmallVec<[u16; 4]> = reader.read_vec_small(LengthPrefix::SevenBitEncodedInt, |r| r.read_u16())?;
+        assert_eq!([1, 2, 3, 4, 5, 6], long.as_slice());
+        assert!(long.spilled());
+        assert!(reader.is_at_eof()?);
+        Ok(())
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn round_trip_uri() -> Result<(), DataDecodeError> {
+        let uri = url::Url::parse("https://example.com/path?query=1#frag").unwrap();
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_uri(&uri)?;
+        writer.write_string("not a uri")?;
+        writer.write_byte(5)?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(uri, reader.read_uri()?);
+        let result = reader.read_uri();
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::InvalidUri))));
+        // the malformed string is still consumed
+        assert_eq!(5, reader.read_byte()?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_ip_address() -> Result<(), DataDecodeError> {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        let v4 = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+        let v6 = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(5, writer.write_ip_address(v4)?);
+        assert_eq!(17, writer.write_ip_address(v6)?);
+        writer.write_byte(6)?;
+        assert_eq!([4, 192, 168, 1, 20, 16, 0x20, 0x01, 0x0d, 0xb8], data[..10]);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(v4, reader.read_ip_address()?);
+        assert_eq!(v6, reader.read_ip_address()?);
+        let result = reader.read_ip_address();
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::OutOfRange))));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_timezone_id() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_timezone_id("Pacific Standard Time")?;
+        writer.write_timezone_id("Europe/London")?;
+        assert_eq!(21, data[0]);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!("Pacific Standard Time", reader.read_timezone_id()?);
+        assert_eq!("Europe/London", reader.read_timezone_id()?);
+        Ok(())
+    }
+
+    #[test]
+    fn read_until_terminator() -> Result<(), DataDecodeError> {
+        let data = [1, 2, 3, 0xFF, 0xFF, 4, 5];
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(vec![1, 2, 3], reader.read_until(0xFF)?);
+        assert!(reader.read_until(0xFF)?.is_empty());
+        assert_eq!(5, reader.num_bytes_read());
+        let result = reader.read_until(0xFF);
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::NotEnoughBytes))));
+        Ok(())
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn round_trip_json() -> Result<(), DataDecodeError> {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Settings {
+            name: String,
+            volume: f32,
+            tags: Vec<String>,
+        }
+
+        let settings = Settings { name: "meow".to_string(), volume: 0.5, tags: vec!["a".to_string(), "b".to_string()] };
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_json(&settings)?;
+        writer.write_string("{\"name\": 5}")?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert_eq!(r#"{"name":"meow","volume":0.5,"tags":["a","b"]}"#, BinaryReader::new(data.as_slice()).read_string()?);
+        assert_eq!(settings, reader.read_json()?);
+        let result = reader.read_json::<Settings>();
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::InvalidJson))));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_byte_array() -> Result<(), DataDecodeError> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        assert_eq!(4, writer.write_byte_array(&[])?);
+        assert_eq!(7, writer.write_byte_array(&[1, 2, 3])?);
+        writer.write_i32(-1)?;
+        assert_eq!([0, 0, 0, 0, 3, 0, 0, 0, 1, 2, 3], data[..11]);
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        assert!(reader.read_byte_array()?.is_empty());
+        assert_eq!(vec![1, 2, 3], reader.read_byte_array()?);
+        let result = reader.read_byte_array();
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::NegativeLength))));
+
+        let mut reader = BinaryReader::new(&data[4..]);
+        reader.set_max_read_length(2);
+        let result = reader.read_byte_array();
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::LengthLimitExceeded))));
+        Ok(())
+    }
+
+    #[test]
+    fn buffered_seek_reader() -> Result<(), DataDecodeError> {
+        use std::io::{Cursor, Read, Seek, SeekFrom};
+
+        // hides Cursor's Seek implementation
+        struct ReadOnly(Cursor<Vec<u8>>);
+
+        impl Read for ReadOnly {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.0.read(buf)
+            }
+        }
+
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_i32(727)?;
+        writer.write_string("meow")?;
+
+        let mut input = BufferedSeekReader::new(ReadOnly(Cursor::new(data.clone())));
+        let mut first = [0; 2];
+        input.read_exact(&mut first)?;
+        assert_eq!(2, input.buffered().len());
+        assert_eq!(0, input.seek(SeekFrom::Start(0))?);
+        assert_eq!(data, input.buffered());
+        assert!(input.seek(SeekFrom::Current(-1)).is_err());
+
+        let mut reader = BinaryReader::new(input);
+        assert_eq!(727, reader.read_i32()?);
+        assert_eq!("meow", reader.read_string()?);
+        assert_eq!(4, reader.seek(SeekFrom::End(-5))?);
+        assert_eq!("meow", reader.read_string()?);
+        reader.seek(SeekFrom::Start(0))?;
+        assert_eq!(727, reader.read_i32()?);
+        Ok(())
+    }
+
+    #[test]
+    fn pipe_string() -> Result<(), DataDecodeError> {
+        // multi-byte characters straddle the 4096 byte chunks
+        let long_string = "a\u{2603}\u{1F408}".repeat(2000);
+        let mut data: Vec<u8> = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+        writer.write_string(&long_string)?;
+        writer.write_7_bit_encoded_int(5000)?;
+        writer.write_bytes(&[b'x'; 4500])?;
+        writer.write_bytes(&[0xFF; 500])?;
+
+        let mut reader = BinaryReader::new(data.as_slice());
+        let mut out: Vec<u8> = Vec::new();
+        assert_eq!(long_string.len(), reader.pipe_string(&mut out)?);
+        assert_eq!(long_string.as_bytes(), out);
+
+        let mut out: Vec<u8> = Vec::new();
+        let result = reader.pipe_string(&mut out);
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::InvalidUtf8))));
+        // the first chunk was valid and already written
+        assert_eq!(vec![b'x'; 4096], out);
+        Ok(())
+    }
+
+    #[test]
+    fn planned_record_size_overflow() {
+        let plan = RecordPlan::new(&[FieldType::Bytes(usize::MAX), FieldType::I32]);
+        assert_eq!(None, plan.fixed_size());
+        let plan = RecordPlan::new(&[FieldType::I32, FieldType::Bytes(usize::MAX - 4), FieldType::U8]);
+        assert_eq!(None, plan.fixed_size());
+        assert_eq!(Some(usize::MAX), RecordPlan::new(&[FieldType::I32, FieldType::Bytes(usize::MAX - 4)]).fixed_size());
+
+        let data = [0u8; 8];
+        let mut reader = BinaryReader::new(data.as_slice());
+        let result = reader.read_planned_record(&plan);
+        assert!(matches!(result, Err(DataDecodeError::InvalidData(InvalidDataError::LengthLimitExceeded))));
+    }
 }